@@ -0,0 +1,63 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{Binary, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError, StdResult};
+use cw721_base::ContractError;
+use msgs::{assert_can_migrate, migrate_attributes};
+
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, NoteMetadata, QueryMsg};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub type NoteContract<'a> = cw721_base::Cw721Contract<'a, NoteMetadata, Empty, Empty, Empty>;
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    let res = NoteContract::default().instantiate(deps.branch(), env, info, msg)?;
+    // cw721-base writes its own version info, we overwrite it
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    let contract = NoteContract::default();
+    match msg {
+        // The depository burns the notes it paid out in full, the holder doesn't have to approve it
+        ExecuteMsg::Burn { token_id }
+            if contract.minter(deps.as_ref())?.minter.as_deref() == Some(info.sender.as_str()) =>
+        {
+            contract.tokens.remove(deps.storage, &token_id)?;
+            contract.decrement_tokens(deps.storage)?;
+            Ok(Response::new()
+                .add_attribute("action", "burn")
+                .add_attribute("sender", info.sender)
+                .add_attribute("token_id", token_id))
+        }
+        msg => contract.execute(deps, env, info, msg),
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    NoteContract::default().query(deps, env, msg)
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)
+        .map_err(StdError::from)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION)))
+}
@@ -0,0 +1,23 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Empty, Timestamp, Uint128};
+
+pub use cw721::{OwnerOfResponse, TokensResponse};
+pub use cw721_base::InstantiateMsg;
+
+/// Bond note sold as an NFT, the depository stays the source of truth for the payout left
+#[cw_serde]
+pub struct NoteMetadata {
+    pub market_id: u64,
+    /// OHM left to redeem when the note was minted
+    pub payout: Uint128,
+    pub vesting_end: Timestamp,
+    /// Token deposited to buy the bond
+    pub quote_token: String,
+}
+
+pub type ExecuteMsg = cw721_base::ExecuteMsg<NoteMetadata, Empty>;
+pub type QueryMsg = cw721_base::QueryMsg<Empty>;
+
+/// Message type for `migrate` entry_point
+#[cw_serde]
+pub struct MigrateMsg {}
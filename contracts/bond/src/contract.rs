@@ -1,52 +1,63 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    to_json_binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response, Uint128,
+    to_json_binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply, Response, Uint128,
 };
+use cw_asset::AssetInfoUnchecked;
+use msgs::{assert_can_migrate, migrate_attributes};
 
 use crate::error::{ContractError, ContractResult, QueryResult};
-use crate::execute::{current_debt, debt_decay, deposit, redeem};
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::execute::{
+    claim_referral_rewards, current_debt, debt_decay, deposit, disable_auto_redeem,
+    instantiate_note_nft, receive, redeem, redeem_for, set_auto_redeem, tokenize_note,
+};
+use crate::helpers::sweep_msgs;
+use crate::migrate::migrate_storage;
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 use crate::query::{
-    bond_info, debt_ratio, max_payout, payout_for, pending_payout_for, percent_vested_for,
-    query_adjustment, query_config, query_terms,
+    auto_redeem, auto_redeemers, bond_discount, debt_ratio, deposit_history, is_allowed,
+    market_price, market_stats, max_payout, payout_for, pending_payout_for, percent_vested_for,
+    query_adjustment, query_adjustment_schedule, query_config, query_market, query_market_tuning,
+    query_markets, query_note, query_notes, query_terms, query_tokenized_note, quote_decimals,
+    quote_value, referral_rewards,
 };
 use crate::state::{
-    query_bond_price, Adjustment, Config, Terms, ADJUSTMENT, CONFIG, LAST_DECAY, TERMS, TOTAL_DEBT,
+    init_auction, load_market, pause_status, query_bond_price, set_paused, Adjustment,
+    AdjustmentStep, AllowList, CalculatorConfig, Config, Market, Operation, OracleConfig, Terms,
+    Tuning, TuningConfig, ADJUSTMENTS, ADJUSTMENT_SCHEDULES, AUCTIONS, CONFIG, DECIMALS, MARKETS,
+    NEXT_MARKET_ID, REFERRERS, TERMS, TUNINGS,
 };
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 /// Handling contract instantiation
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
-    env: Env,
+    _env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner = msg.owner.unwrap_or_else(|| info.sender.to_string());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+
     let config = Config {
-        principle: msg.principle,
-        admin: msg
-            .admin
-            .map(|addr| deps.api.addr_validate(&addr))
-            .transpose()?
-            .unwrap_or(info.sender),
         staking: deps.api.addr_validate(&msg.staking)?,
         treasury: deps.api.addr_validate(&msg.treasury)?,
+        guardian: msg
+            .guardian
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
+        note_nft: None,
+        dao_fee: Decimal256::zero(),
+        dao_address: None,
+        lock_contract: None,
     };
 
     CONFIG.save(deps.storage, &config)?;
-    TERMS.save(deps.storage, &msg.terms)?;
-    LAST_DECAY.save(deps.storage, &env.block.time)?;
-    TOTAL_DEBT.save(deps.storage, &Uint128::zero())?;
-    ADJUSTMENT.save(
-        deps.storage,
-        &Adjustment {
-            add: true,
-            rate: Decimal256::zero(),
-            target: Decimal256::zero(),
-            buffer: 0,
-            last_time: env.block.time,
-        },
-    )?;
+    NEXT_MARKET_ID.save(deps.storage, &0)?;
 
     Ok(Response::new())
 }
@@ -61,23 +72,95 @@ pub fn execute(
 ) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Deposit {
+            market_id,
             max_price,
             depositor,
-        } => deposit(deps, env, info, max_price, depositor),
-        ExecuteMsg::Redeem { recipient, stake } => redeem(deps, env, info, recipient, stake),
-        ExecuteMsg::UpdateTerms { terms } => update_terms(deps, info, terms),
+            referrer,
+            proof,
+            callback,
+        } => deposit(
+            deps, env, info, market_id, max_price, depositor, referrer, proof, callback,
+        ),
+        ExecuteMsg::RedeemFor { recipients, stake } => {
+            redeem_for(deps, env, info, recipients, stake)
+        }
+        ExecuteMsg::SetAutoRedeem { stake, tip } => set_auto_redeem(deps, info, stake, tip),
+        ExecuteMsg::DisableAutoRedeem {} => disable_auto_redeem(deps, info),
+        ExecuteMsg::Receive(msg) => receive(deps, env, info, msg),
+        ExecuteMsg::Redeem {
+            recipient,
+            stake,
+            note_ids,
+        } => redeem(deps, env, info, recipient, stake, note_ids),
+        ExecuteMsg::CreateMarket {
+            quote_token,
+            capacity_in_quote,
+            capacity,
+            terms,
+            oracle,
+            calculator,
+            tuning,
+            allow_list,
+        } => create_market(
+            deps,
+            env,
+            info,
+            quote_token,
+            capacity_in_quote,
+            capacity,
+            terms,
+            oracle,
+            calculator,
+            tuning,
+            allow_list,
+        ),
+        ExecuteMsg::CloseMarket { market_id } => close_market(deps, info, market_id),
+        ExecuteMsg::InstantiateNoteNft {
+            code_id,
+            name,
+            symbol,
+        } => instantiate_note_nft(deps, env, info, code_id, name, symbol),
+        ExecuteMsg::TokenizeNote { note_id } => tokenize_note(deps, info, note_id),
+        ExecuteMsg::UpdateTerms { market_id, terms } => {
+            update_terms(deps, env, info, market_id, terms)
+        }
         ExecuteMsg::UpdateConfig {
-            principle,
-            admin,
             staking,
             treasury,
-        } => update_config(deps, info, principle, admin, staking, treasury),
+            guardian,
+            dao_fee,
+            dao_address,
+            lock_contract,
+        } => update_config(
+            deps,
+            info,
+            staking,
+            treasury,
+            guardian,
+            dao_fee,
+            dao_address,
+            lock_contract,
+        ),
         ExecuteMsg::UpdateAdjustment {
+            market_id,
             add,
             rate,
             target,
             buffer,
-        } => update_adjustment(deps, info, add, rate, target, buffer),
+        } => update_adjustment(deps, info, market_id, add, rate, target, buffer),
+        ExecuteMsg::ScheduleAdjustments { market_id, steps } => {
+            schedule_adjustments(deps, info, market_id, steps)
+        }
+        ExecuteMsg::SetReferrer { referrer, rate } => set_referrer(deps, info, referrer, rate),
+        ExecuteMsg::ClaimReferralRewards {} => claim_referral_rewards(deps, info),
+        ExecuteMsg::SetDecimals { asset, decimals } => set_decimals(deps, info, asset, decimals),
+        ExecuteMsg::RemoveGuardian {} => remove_guardian(deps, info),
+        ExecuteMsg::Pause { operations } => pause(deps, info, operations),
+        ExecuteMsg::Unpause { operations } => unpause(deps, info, operations),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
     }
 }
 
@@ -86,59 +169,294 @@ pub fn execute(
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> QueryResult {
     match msg {
         QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
-        QueryMsg::Terms {} => Ok(to_json_binary(&query_terms(deps)?)?),
-        QueryMsg::Adjustment {} => Ok(to_json_binary(&query_adjustment(deps)?)?),
-        QueryMsg::MaxPayout {} => Ok(to_json_binary(&max_payout(deps)?)?),
-        QueryMsg::PayoutFor { value } => Ok(to_json_binary(&payout_for(deps, env, value)?)?),
-        QueryMsg::BondPrice {} => Ok(to_json_binary(&query_bond_price(deps, env)?)?),
-        QueryMsg::DebtRatio {} => Ok(to_json_binary(&debt_ratio(deps, env)?)?),
-        QueryMsg::CurrentDebt {} => Ok(to_json_binary(&current_debt(deps, env)?)?),
-        QueryMsg::DebtDecay {} => Ok(to_json_binary(&debt_decay(deps, env)?)?),
-        QueryMsg::PercentVestedFor { recipient } => Ok(to_json_binary(&percent_vested_for(
+        QueryMsg::Markets {} => Ok(to_json_binary(&query_markets(deps)?)?),
+        QueryMsg::Market { id } => Ok(to_json_binary(&query_market(deps, id)?)?),
+        QueryMsg::Terms { market_id } => Ok(to_json_binary(&query_terms(deps, market_id)?)?),
+        QueryMsg::Adjustment { market_id } => {
+            Ok(to_json_binary(&query_adjustment(deps, market_id)?)?)
+        }
+        QueryMsg::AdjustmentSchedule { market_id } => Ok(to_json_binary(
+            &query_adjustment_schedule(deps, market_id)?,
+        )?),
+        QueryMsg::MarketTuning { market_id } => {
+            Ok(to_json_binary(&query_market_tuning(deps, market_id)?)?)
+        }
+        QueryMsg::MaxPayout { market_id } => Ok(to_json_binary(&max_payout(deps, market_id)?)?),
+        QueryMsg::QuoteValue { market_id, amount } => {
+            Ok(to_json_binary(&quote_value(deps, market_id, amount)?)?)
+        }
+        QueryMsg::PayoutFor { market_id, value } => {
+            Ok(to_json_binary(&payout_for(deps, env, market_id, value)?)?)
+        }
+        QueryMsg::BondPrice { market_id } => {
+            Ok(to_json_binary(&query_bond_price(deps, env, market_id)?)?)
+        }
+        QueryMsg::MarketPrice { market_id } => Ok(to_json_binary(&market_price(deps, market_id)?)?),
+        QueryMsg::BondDiscount { market_id } => {
+            Ok(to_json_binary(&bond_discount(deps, env, market_id)?)?)
+        }
+        QueryMsg::DebtRatio { market_id } => {
+            Ok(to_json_binary(&debt_ratio(deps, env, market_id)?)?)
+        }
+        QueryMsg::CurrentDebt { market_id } => {
+            Ok(to_json_binary(&current_debt(deps, env, market_id)?)?)
+        }
+        QueryMsg::DebtDecay { market_id } => {
+            Ok(to_json_binary(&debt_decay(deps, env, market_id)?)?)
+        }
+        QueryMsg::PercentVestedFor { recipient, note_id } => Ok(to_json_binary(
+            &percent_vested_for(deps, env, &deps.api.addr_validate(&recipient)?, note_id)?,
+        )?),
+        QueryMsg::PendingPayoutFor { recipient } => {
+            Ok(to_json_binary(&pending_payout_for(deps, env, recipient)?)?)
+        }
+        QueryMsg::Note { owner, note_id } => {
+            Ok(to_json_binary(&query_note(deps, owner, note_id)?)?)
+        }
+        QueryMsg::Notes {
+            owner,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&query_notes(
             deps,
             env,
-            &deps.api.addr_validate(&recipient)?,
+            owner,
+            start_after,
+            limit,
         )?)?),
-        QueryMsg::PendingPayoutFor { recipient } => {
-            Ok(to_json_binary(&pending_payout_for(deps, env, recipient)?)?)
+        QueryMsg::TokenizedNote { note_id } => {
+            Ok(to_json_binary(&query_tokenized_note(deps, note_id)?)?)
         }
-        QueryMsg::BondInfo { recipient } => Ok(to_json_binary(&bond_info(deps, recipient)?)?),
+        QueryMsg::IsAllowed {
+            market_id,
+            address,
+            proof,
+        } => Ok(to_json_binary(&is_allowed(
+            deps, market_id, address, proof,
+        )?)?),
+        QueryMsg::DepositHistory {
+            address,
+            start_after,
+            limit,
+        } => Ok(to_json_binary(&deposit_history(
+            deps,
+            address,
+            start_after,
+            limit,
+        )?)?),
+        QueryMsg::MarketStats {} => Ok(to_json_binary(&market_stats(deps)?)?),
+        QueryMsg::AutoRedeem { address } => Ok(to_json_binary(&auto_redeem(deps, address)?)?),
+        QueryMsg::AutoRedeemers { start_after, limit } => {
+            Ok(to_json_binary(&auto_redeemers(deps, start_after, limit)?)?)
+        }
+        QueryMsg::ReferralRewards { referrer } => {
+            Ok(to_json_binary(&referral_rewards(deps, referrer)?)?)
+        }
+        QueryMsg::Decimals { asset } => Ok(to_json_binary(&quote_decimals(
+            deps,
+            &asset.check(deps.api, None)?,
+        )?)?),
+        QueryMsg::PauseStatus {} => Ok(to_json_binary(&pause_status(deps.storage)?)?),
+        QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
     }
 }
 
-pub fn update_terms(deps: DepsMut, info: MessageInfo, terms: Terms) -> ContractResult {
-    if info.sender != CONFIG.load(deps.storage)?.admin {
-        return Err(ContractError::Unauthorized {});
+pub const INSTANTIATE_NOTE_NFT_REPLY: u64 = 1;
+
+/// Handling submessage replies
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> ContractResult {
+    match reply.id {
+        INSTANTIATE_NOTE_NFT_REPLY => {
+            // We register the instantiated contract in the config
+            let res = cw_utils::parse_reply_instantiate_data(reply)?;
+            let note_nft = deps.api.addr_validate(&res.contract_address)?;
+
+            CONFIG.update(deps.storage, |mut c| {
+                c.note_nft = Some(note_nft.clone());
+                Ok::<_, ContractError>(c)
+            })?;
+            Ok(Response::new().add_attribute("note-nft", note_nft))
+        }
+        _ => Err(ContractError::InvalidReplyId {}),
+    }
+}
+
+/// Handling governance calls, they bypass the owner and guardian checks
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult {
+    match msg {
+        SudoMsg::UpdateOwner { owner } => {
+            let ownership = cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+            Ok(Response::new()
+                .add_attribute("action", "sudo_update_owner")
+                .add_attributes(ownership.into_attributes()))
+        }
+        SudoMsg::Pause { operations } => {
+            set_paused(deps.storage, &operations, true)?;
+            Ok(Response::new().add_attribute("action", "sudo_pause"))
+        }
+        SudoMsg::Unpause { operations } => {
+            set_paused(deps.storage, &operations, false)?;
+            Ok(Response::new().add_attribute("action", "sudo_unpause"))
+        }
+        SudoMsg::SweepFunds { recipient } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            Ok(Response::new()
+                .add_messages(sweep_msgs(deps.as_ref(), &env, &recipient)?)
+                .add_attribute("action", "sudo_sweep_funds")
+                .add_attribute("recipient", recipient))
+        }
+    }
+}
+
+/// Handling contract migration
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> ContractResult {
+    let from_version = assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    migrate_storage(deps.branch(), from_version.as_ref(), msg.decimals)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION)))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn create_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    quote_token: AssetInfoUnchecked,
+    capacity_in_quote: bool,
+    capacity: Uint128,
+    terms: Terms,
+    oracle: Option<OracleConfig>,
+    calculator: Option<CalculatorConfig>,
+    tuning: Option<TuningConfig>,
+    allow_list: Option<AllowList>,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+
+    let market_id = NEXT_MARKET_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_MARKET_ID.save(deps.storage, &(market_id + 1))?;
+
+    let market = Market {
+        quote_token: quote_token.check(deps.api, None)?,
+        capacity_in_quote,
+        capacity,
+        total_debt: Uint128::zero(),
+        last_decay: env.block.time,
+        sold: Uint128::zero(),
+        purchased: Uint128::zero(),
+        oracle: oracle.map(|oracle| oracle.validate(deps.api)).transpose()?,
+        calculator: calculator
+            .map(|calculator| calculator.validate(deps.api))
+            .transpose()?,
+        allow_list: allow_list.map(AllowList::validate).transpose()?,
+    };
+
+    // Deposits can't be priced without the decimals of the quote token
+    quote_decimals(deps.as_ref(), &market.quote_token)?;
+    terms.validate(&CONFIG.load(deps.storage)?)?;
+    MARKETS.save(deps.storage, market_id, &market)?;
+    TERMS.save(deps.storage, market_id, &terms)?;
+    init_auction(deps.storage, market_id, &terms, env.block.time)?;
+    if let Some(tuning) = tuning {
+        TUNINGS.save(
+            deps.storage,
+            market_id,
+            &Tuning {
+                config: tuning.validate(&env)?,
+                last_tune: env.block.time,
+            },
+        )?;
+    }
+    ADJUSTMENTS.save(
+        deps.storage,
+        market_id,
+        &Adjustment {
+            add: true,
+            rate: Decimal256::zero(),
+            target: Decimal256::zero(),
+            buffer: 0,
+            last_time: env.block.time,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_attribute("action", "create_market")
+        .add_attribute("market_id", market_id.to_string())
+        .add_attribute("quote_token", market.quote_token.to_string()))
+}
+
+pub fn close_market(deps: DepsMut, info: MessageInfo, market_id: u64) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut market = load_market(deps.storage, market_id)?;
+    market.capacity = Uint128::zero();
+    MARKETS.save(deps.storage, market_id, &market)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "close_market")
+        .add_attribute("market_id", market_id.to_string()))
+}
+
+pub fn update_terms(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    market_id: u64,
+    terms: Terms,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    load_market(deps.storage, market_id)?;
+    terms.validate(&CONFIG.load(deps.storage)?)?;
+    let previous = TERMS.load(deps.storage, market_id)?;
+    TERMS.save(deps.storage, market_id, &terms)?;
+    // New auction parameters start a new auction
+    if previous.pricing != terms.pricing {
+        AUCTIONS.remove(deps.storage, market_id);
     }
-    TERMS.save(deps.storage, &terms)?;
+    init_auction(deps.storage, market_id, &terms, env.block.time)?;
     Ok(Response::new())
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
-    principle: Option<String>,
-    admin: Option<String>,
     staking: Option<String>,
     treasury: Option<String>,
+    guardian: Option<String>,
+    dao_fee: Option<Decimal256>,
+    dao_address: Option<String>,
+    lock_contract: Option<String>,
 ) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
     let mut config = CONFIG.load(deps.storage)?;
-    if info.sender != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
 
-    if let Some(principle) = principle {
-        config.principle = principle;
-    }
-    if let Some(admin) = admin {
-        config.admin = deps.api.addr_validate(&admin)?;
-    }
     if let Some(staking) = staking {
         config.staking = deps.api.addr_validate(&staking)?;
     }
     if let Some(treasury) = treasury {
         config.treasury = deps.api.addr_validate(&treasury)?;
     }
+    if let Some(guardian) = guardian {
+        config.guardian = Some(deps.api.addr_validate(&guardian)?);
+    }
+    if let Some(dao_fee) = dao_fee {
+        config.dao_fee = dao_fee;
+    }
+    if let Some(dao_address) = dao_address {
+        config.dao_address = Some(deps.api.addr_validate(&dao_address)?);
+    }
+    if let Some(lock_contract) = lock_contract {
+        config.lock_contract = Some(deps.api.addr_validate(&lock_contract)?);
+    }
+    if config.dao_fee >= Decimal256::one()
+        || (!config.dao_fee.is_zero() && config.dao_address.is_none())
+    {
+        return Err(ContractError::InvalidDaoFee {});
+    }
 
     CONFIG.save(deps.storage, &config)?;
 
@@ -148,15 +466,15 @@ pub fn update_config(
 pub fn update_adjustment(
     deps: DepsMut,
     info: MessageInfo,
+    market_id: u64,
     add: Option<bool>,
     rate: Option<Decimal256>,
     target: Option<Decimal256>,
     buffer: Option<u64>,
 ) -> ContractResult {
-    if info.sender != CONFIG.load(deps.storage)?.admin {
-        return Err(ContractError::Unauthorized {});
-    }
-    let mut adjustment = ADJUSTMENT.load(deps.storage)?;
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    load_market(deps.storage, market_id)?;
+    let mut adjustment = ADJUSTMENTS.load(deps.storage, market_id)?;
 
     if let Some(add) = add {
         adjustment.add = add;
@@ -174,11 +492,111 @@ pub fn update_adjustment(
         adjustment.buffer = buffer;
     }
 
-    ADJUSTMENT.save(deps.storage, &adjustment)?;
+    ADJUSTMENTS.save(deps.storage, market_id, &adjustment)?;
 
     Ok(Response::new())
 }
 
+pub fn schedule_adjustments(
+    deps: DepsMut,
+    info: MessageInfo,
+    market_id: u64,
+    steps: Vec<AdjustmentStep>,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    load_market(deps.storage, market_id)?;
+    if steps.windows(2).any(|pair| pair[0].start > pair[1].start) {
+        return Err(ContractError::InvalidSchedule {});
+    }
+    ADJUSTMENT_SCHEDULES.save(deps.storage, market_id, &steps)?;
+
+    Ok(Response::new()
+        .add_attribute("action", "schedule_adjustments")
+        .add_attribute("market_id", market_id.to_string())
+        .add_attribute("steps", steps.len().to_string()))
+}
+
+pub fn set_referrer(
+    deps: DepsMut,
+    info: MessageInfo,
+    referrer: String,
+    rate: Option<Decimal256>,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let referrer = deps.api.addr_validate(&referrer)?;
+
+    match rate {
+        Some(rate) => {
+            if rate >= Decimal256::one() {
+                return Err(ContractError::InvalidReferralRate {});
+            }
+            REFERRERS.save(deps.storage, &referrer, &rate)?;
+        }
+        None => REFERRERS.remove(deps.storage, &referrer),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_referrer")
+        .add_attribute("referrer", referrer)
+        .add_attribute("rate", rate.map(|r| r.to_string()).unwrap_or_default()))
+}
+
+pub fn set_decimals(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfoUnchecked,
+    decimals: Option<u8>,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let asset = asset.check(deps.api, None)?;
+
+    match decimals {
+        Some(decimals) => {
+            if u32::from(decimals) > Decimal256::DECIMAL_PLACES {
+                return Err(ContractError::InvalidDecimals {});
+            }
+            DECIMALS.save(deps.storage, &asset, &decimals)?;
+        }
+        None => DECIMALS.remove(deps.storage, &asset),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_decimals")
+        .add_attribute("asset", asset.to_string())
+        .add_attribute(
+            "decimals",
+            decimals.map(|d| d.to_string()).unwrap_or_default(),
+        ))
+}
+
+pub fn remove_guardian(deps: DepsMut, info: MessageInfo) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.guardian = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "remove_guardian"))
+}
+
+pub fn pause(deps: DepsMut, info: MessageInfo, operations: Vec<Operation>) -> ContractResult {
+    let config = CONFIG.load(deps.storage)?;
+    if !cw_ownable::is_owner(deps.storage, &info.sender)?
+        && Some(&info.sender) != config.guardian.as_ref()
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    set_paused(deps.storage, &operations, true)?;
+
+    Ok(Response::new().add_attribute("action", "pause"))
+}
+
+pub fn unpause(deps: DepsMut, info: MessageInfo, operations: Vec<Operation>) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    set_paused(deps.storage, &operations, false)?;
+
+    Ok(Response::new().add_attribute("action", "unpause"))
+}
+
 #[cfg(test)]
 pub mod test {
     use std::rc::Rc;
@@ -186,27 +604,54 @@ pub mod test {
 
     use cosmwasm_std::coin;
     use cosmwasm_std::coins;
+    use cosmwasm_std::to_json_binary;
     use cosmwasm_std::Decimal256;
     use cosmwasm_std::Uint128;
     use cw_orch::injective_test_tube::injective_test_tube::SigningAccount;
     use cw_orch::{injective_test_tube::InjectiveTestTube, prelude::*};
 
     use bond::interface::Bond;
+    use bond::msg::AutoRedeemerResponse;
+    use bond::msg::BondDiscountResponse;
+    use bond::msg::ExecuteMsg;
     use bond::msg::ExecuteMsgFns as _;
+    use bond::msg::MarketStatsResponse;
     use bond::msg::QueryMsgFns;
-    use bond::state::Terms;
+    use bond::msg::ReceiveMsg;
+    use bond::msg::SudoMsg;
+    use bond::state::{AdjustmentStep, AllowList, CalculatorConfig, TuningConfig};
+    use bond::state::{AuctionParams, PricingModel, Terms, VestingMode};
+    use bond::state::{AutoRedeem, HistoryEntry, HistoryEvent};
+    use bond::state::{Operation, PauseStatus};
+    use bond::state::{OracleConfig, OracleSource};
+    use bond_note::msg::NoteMetadata;
+    use bonding_calculator::interface::BondingCalculator;
+    use bonding_calculator::msg::QueryMsgFns as _;
+    use cw20::{Cw20Coin, Cw20ReceiveMsg};
+    use cw721::{NftInfoResponse, OwnerOfResponse};
+    use cw_asset::AssetInfoUnchecked;
     use cw_orch::injective_test_tube::injective_test_tube::Account;
     use cw_plus_interface::cw1_whitelist::Cw1Whitelist;
+    use cw_plus_interface::cw20_base::Cw20Base;
+    use lock_contract::interface::LockContract;
+    use lock_contract::msg::{AcceptedTokenUnchecked, ExecuteMsgFns as _, QueryMsgFns as _};
+    use lock_contract::state::deposit::{locks::LinearLock, DepositLock};
+    use lock_contract::state::Fee;
+    use mock_pool::interface::MockPool;
+    use oracle::interface::Oracle;
+    use sha2::{Digest, Sha256};
     use staking_contract::msg::QueryMsgFns as _;
     use tests::deploy::upload::BondConfig;
     use tests::deploy::upload::Shogun;
     use tests::deploy::upload::ShogunDeployment;
-    use tests::tokenfactory::assert_balance;
+    use tests::sudo::wasm_sudo;
+    use tests::tokenfactory::{assert_balance, assert_cw20_balance};
 
     pub const AMOUNT_TO_CREATE_DENOM: u128 = 10_000_000_000_000_000_000u128;
     pub const FUNDS_MULTIPLIER: u128 = 100_000;
     pub const BOND_TOKEN: &str = "ubond";
     pub const USD_TOKEN: &str = "uusd";
+    pub const MARKET_ID: u64 = 0;
 
     pub fn init() -> anyhow::Result<(
         Shogun<InjectiveTestTube>,
@@ -238,29 +683,33 @@ pub mod test {
                 staking_name: "sSHOGUN".to_string(),
                 warmup_length: 50,
                 cw1_code_id: cw1.code_id()?,
-            },
-        )?;
-
-        shogun.add_bond(
-            chain,
-            BondConfig {
-                bond_token_denom: BOND_TOKEN.to_string(),
                 treasury: treasury.address(),
-                terms: Terms {
-                    control_variable: Decimal256::from_str("1000")?,
-                    minimum_price: Decimal256::from_str("2")?,
-                    max_payout: Decimal256::from_str("0.2")?,
-                    max_debt: 500_000u128.into(),
-                    vesting_term: 3600u64, // 1h
-                },
+                note_name: "Shogun bond notes".to_string(),
+                note_symbol: "SBOND".to_string(),
             },
         )?;
 
-        Ok((
-            shogun.clone(),
-            shogun.bonds.get(BOND_TOKEN).unwrap().clone(),
-            treasury,
-        ))
+        shogun.add_market(BondConfig {
+            bond_token_denom: BOND_TOKEN.to_string(),
+            bond_token_decimals: 6,
+            capacity_in_quote: false,
+            capacity: Uint128::MAX,
+            terms: terms()?,
+        })?;
+
+        Ok((shogun.clone(), shogun.bond.clone(), treasury))
+    }
+
+    pub fn terms() -> anyhow::Result<Terms> {
+        Ok(Terms {
+            control_variable: Decimal256::from_str("1000")?,
+            minimum_price: Decimal256::from_str("2")?,
+            max_payout: Decimal256::from_str("0.2")?,
+            max_debt: 500_000u128.into(),
+            vesting: VestingMode::FixedTerm(3600), // 1h
+            pricing: PricingModel::DebtRatio,
+            lock_payout: false,
+        })
     }
 
     #[test]
@@ -276,11 +725,15 @@ pub mod test {
         let chain = bond.get_chain().clone();
 
         let max_price = Decimal256::from_str("2")?;
-        assert_eq!(bond.bond_price()?, Decimal256::from_str("2")?);
+        assert_eq!(bond.bond_price(MARKET_ID)?, Decimal256::from_str("2")?);
 
         bond.deposit(
+            None,
             chain.sender().to_string(),
+            MARKET_ID,
             max_price,
+            None,
+            None,
             &coins(10_000, BOND_TOKEN),
         )?;
 
@@ -291,19 +744,20 @@ pub mod test {
             treasury.address().to_string(),
         )?;
 
-        // assert bond exists and has the right terms
-        let term = bond.bond_info(chain.sender().to_string())?;
+        // assert note exists and has the right terms
+        let term = bond.note(0, chain.sender().to_string())?;
 
         assert_eq!(
             term,
-            bond::state::Bond {
+            bond::state::Note {
+                market_id: MARKET_ID,
                 payout: 5_000u128.into(),
                 vesting_time_left: 3600,
                 last_time: chain.block_info()?.time
             }
         );
 
-        assert!(bond.current_debt()? > Uint128::zero());
+        assert!(bond.current_debt(MARKET_ID)? > Uint128::zero());
 
         Ok(())
     }
@@ -316,8 +770,12 @@ pub mod test {
         let max_price = Decimal256::from_str("1.9")?;
         let err = bond
             .deposit(
+                None,
                 chain.sender().to_string(),
+                MARKET_ID,
                 max_price,
+                None,
+                None,
                 &coins(10_000, BOND_TOKEN),
             )
             .unwrap_err();
@@ -336,8 +794,12 @@ pub mod test {
         let max_price = Decimal256::from_str("2")?;
         let err = bond
             .deposit(
+                None,
                 chain.sender().to_string(),
+                MARKET_ID,
                 max_price,
+                None,
+                None,
                 &coins(400_002, BOND_TOKEN),
             )
             .unwrap_err();
@@ -350,13 +812,18 @@ pub mod test {
     pub fn not_too_much_debt() -> anyhow::Result<()> {
         let (_, bond, _treasury) = init()?;
 
-        bond.update_terms(Terms {
-            control_variable: Decimal256::from_str("1")?,
-            minimum_price: Decimal256::from_str("2")?,
-            max_payout: Decimal256::from_str("2")?,
-            max_debt: 500_000u128.into(),
-            vesting_term: 3600u64,
-        })?;
+        bond.update_terms(
+            MARKET_ID,
+            Terms {
+                control_variable: Decimal256::from_str("1")?,
+                minimum_price: Decimal256::from_str("2")?,
+                max_payout: Decimal256::from_str("2")?,
+                max_debt: 500_000u128.into(),
+                vesting: VestingMode::FixedTerm(3600),
+                pricing: PricingModel::DebtRatio,
+                lock_payout: false,
+            },
+        )?;
 
         let chain = bond.get_chain().clone();
 
@@ -364,12 +831,24 @@ pub mod test {
         // We can't deposit more than 400_000 at a time though
         let max_price = Decimal256::from_str("2")?;
         bond.deposit(
+            None,
             chain.sender().to_string(),
+            MARKET_ID,
             max_price,
+            None,
+            None,
             &coins(1_000_002, BOND_TOKEN),
         )?;
         let err = bond
-            .deposit(chain.sender().to_string(), max_price, &coins(1, BOND_TOKEN))
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                MARKET_ID,
+                max_price,
+                None,
+                None,
+                &coins(1, BOND_TOKEN),
+            )
             .unwrap_err();
 
         assert!(err.to_string().contains("Max capacity reached"));
@@ -385,20 +864,25 @@ pub mod test {
         let max_price = Decimal256::from_str("5")?;
 
         bond.deposit(
+            None,
             chain.sender().to_string(),
+            MARKET_ID,
             max_price,
+            None,
+            None,
             &coins(10_000, BOND_TOKEN),
         )?;
 
         bond.deposit(
+            None,
             chain.sender().to_string(),
+            MARKET_ID,
             max_price,
+            None,
+            None,
             &coins(10_000, BOND_TOKEN),
         )?;
 
-        // assert bond exists and has the right terms
-        let term = bond.bond_info(chain.sender().to_string())?;
-
         assert_balance(
             chain.clone(),
             BOND_TOKEN.to_string(),
@@ -406,10 +890,15 @@ pub mod test {
             treasury.address().to_string(),
         )?;
 
+        // each deposit has its own note
+        let notes = bond.notes(None, chain.sender().to_string(), None)?;
+        assert_eq!(notes.iter().map(|n| n.id).collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(notes[0].note.payout.u128(), 5_000);
         assert_eq!(
-            term,
-            bond::state::Bond {
-                payout: 7010u128.into(),
+            notes[1].note,
+            bond::state::Note {
+                market_id: MARKET_ID,
+                payout: 2_010u128.into(),
                 vesting_time_left: 3600,
                 last_time: chain.block_info()?.time
             }
@@ -426,27 +915,32 @@ pub mod test {
         let max_price = Decimal256::from_str("2.5")?;
 
         bond.deposit(
+            None,
             chain.sender().to_string(),
+            MARKET_ID,
             max_price,
+            None,
+            None,
             &coins(10_000, BOND_TOKEN),
         )?;
 
         chain.wait_blocks(180)?;
 
         assert_eq!(
-            bond.percent_vested_for(chain.sender().to_string())?,
+            bond.percent_vested_for(0, chain.sender().to_string())?,
             Decimal256::from_str("0.5")?
         );
 
         bond.deposit(
+            None,
             chain.sender().to_string(),
+            MARKET_ID,
             max_price,
+            None,
+            None,
             &coins(10_000, BOND_TOKEN),
         )?;
 
-        // assert bond exists and has the right terms
-        let term = bond.bond_info(chain.sender().to_string())?;
-
         assert_balance(
             chain.clone(),
             BOND_TOKEN.to_string(),
@@ -454,7 +948,18 @@ pub mod test {
             treasury.address().to_string(),
         )?;
 
-        assert_eq!(term.payout.u128(), 9_021);
+        // The new deposit doesn't delay the first note
+        assert!(
+            bond.percent_vested_for(0, chain.sender().to_string())? >= Decimal256::from_str("0.5")?
+        );
+        assert_eq!(
+            bond.note(0, chain.sender().to_string())?.payout.u128(),
+            5_000
+        );
+        assert_eq!(
+            bond.note(1, chain.sender().to_string())?.payout.u128(),
+            4_021
+        );
 
         Ok(())
     }
@@ -468,14 +973,18 @@ pub mod test {
         let receiver = chain.init_account(vec![])?;
 
         bond.deposit(
+            None,
             receiver.address().to_string(),
+            MARKET_ID,
             max_price,
+            None,
+            None,
             &coins(10_000, BOND_TOKEN),
         )?;
 
         chain.wait_blocks(180)?;
 
-        bond.redeem(receiver.address().to_string(), false)?;
+        bond.redeem(None, receiver.address().to_string(), false)?;
 
         assert_balance(
             chain.clone(),
@@ -484,12 +993,12 @@ pub mod test {
             receiver.address().to_string(),
         )?;
 
-        bond.redeem(receiver.address().to_string(), false)
+        bond.redeem(None, receiver.address().to_string(), false)
             .unwrap_err();
 
         chain.wait_blocks(180)?;
 
-        bond.redeem(receiver.address().to_string(), false)?;
+        bond.redeem(None, receiver.address().to_string(), false)?;
         assert_balance(
             chain.clone(),
             shogun.staking.config()?.ohm_denom,
@@ -499,4 +1008,1624 @@ pub mod test {
 
         Ok(())
     }
+
+    #[test]
+    pub fn notes_redeem_independently() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let receiver = chain.init_account(vec![])?;
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+
+        let max_price = Decimal256::from_str("5")?;
+        for _ in 0..3 {
+            bond.deposit(
+                None,
+                receiver.address().to_string(),
+                MARKET_ID,
+                max_price,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )?;
+        }
+
+        // Pagination over the notes of the owner
+        let page = bond.notes(Some(1), receiver.address().to_string(), Some(1))?;
+        assert_eq!(page.len(), 1);
+        assert_eq!(page[0].id, 2);
+
+        chain.wait_seconds(3600)?;
+
+        let notes = bond.notes(None, receiver.address().to_string(), None)?;
+        let total: Uint128 = notes.iter().map(|n| n.pending_payout).sum();
+        assert_eq!(
+            bond.pending_payout_for(receiver.address().to_string())?,
+            total
+        );
+
+        // Only the selected notes are redeemed
+        bond.redeem(Some(vec![0]), receiver.address().to_string(), false)?;
+        assert_balance(
+            chain.clone(),
+            ohm_denom.clone(),
+            notes[0].note.payout.u128(),
+            receiver.address().to_string(),
+        )?;
+        bond.note(0, receiver.address().to_string()).unwrap_err();
+        bond.note(1, receiver.address().to_string())?;
+
+        bond.redeem(None, receiver.address().to_string(), false)?;
+        assert_balance(
+            chain.clone(),
+            ohm_denom,
+            total.u128(),
+            receiver.address().to_string(),
+        )?;
+        assert!(bond
+            .notes(None, receiver.address().to_string(), None)?
+            .is_empty());
+
+        // Unknown notes can't be redeemed
+        let err = bond
+            .redeem(Some(vec![0]), receiver.address().to_string(), false)
+            .unwrap_err();
+        assert!(err.to_string().contains("Note 0 doesn't exist"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn tokenized_notes_pay_the_holder() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let buyer = chain.init_account(vec![])?;
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            Decimal256::from_str("2")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        let note = bond.note(0, chain.sender().to_string())?;
+
+        bond.tokenize_note(0)?;
+        bond.note(0, chain.sender().to_string()).unwrap_err();
+        assert_eq!(bond.tokenized_note(0)?, note);
+
+        let nft_info: NftInfoResponse<NoteMetadata> =
+            shogun.bond_note.query(&bond_note::msg::QueryMsg::NftInfo {
+                token_id: "0".to_string(),
+            })?;
+        assert_eq!(
+            nft_info.extension,
+            NoteMetadata {
+                market_id: MARKET_ID,
+                payout: note.payout,
+                vesting_end: note.last_time.plus_seconds(3600),
+                quote_token: BOND_TOKEN.to_string(),
+            }
+        );
+
+        // The note is sold before maturity
+        shogun.bond_note.execute(
+            &bond_note::msg::ExecuteMsg::TransferNft {
+                recipient: buyer.address(),
+                token_id: "0".to_string(),
+            },
+            None,
+        )?;
+
+        chain.wait_seconds(3600)?;
+
+        // The pending payout follows the token
+        assert_eq!(bond.pending_payout_for(buyer.address())?, note.payout);
+        assert!(bond
+            .pending_payout_for(chain.sender().to_string())?
+            .is_zero());
+
+        let err = bond
+            .redeem(Some(vec![0]), chain.sender().to_string(), false)
+            .unwrap_err();
+        assert!(err.to_string().contains("Unauthorized"));
+
+        bond.redeem(Some(vec![0]), buyer.address(), false)?;
+        assert_balance(
+            chain.clone(),
+            ohm_denom,
+            note.payout.u128(),
+            buyer.address(),
+        )?;
+        bond.tokenized_note(0).unwrap_err();
+
+        // The fully vested note is burned
+        shogun
+            .bond_note
+            .query::<OwnerOfResponse>(&bond_note::msg::QueryMsg::OwnerOf {
+                token_id: "0".to_string(),
+                include_expired: None,
+            })
+            .unwrap_err();
+        assert!(bond.pending_payout_for(buyer.address())?.is_zero());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn market_capacity_is_enforced() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let chain = bond.get_chain().clone();
+
+        bond.create_market(
+            None,
+            None,
+            15_000u128.into(),
+            true,
+            None,
+            AssetInfoUnchecked::native(BOND_TOKEN),
+            terms()?,
+            None,
+        )?;
+        let capped_market = MARKET_ID + 1;
+
+        let max_price = Decimal256::from_str("5")?;
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            capped_market,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        let market = bond.market(capped_market)?.market;
+        assert_eq!(market.capacity, Uint128::from(5_000u128));
+        assert_eq!(market.purchased, Uint128::from(10_000u128));
+        assert_eq!(market.sold, Uint128::from(5_000u128));
+
+        let err = bond
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                capped_market,
+                max_price,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("enough capacity"));
+
+        // Notes remember their market
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        let notes = bond.notes(None, chain.sender().to_string(), None)?;
+        assert_eq!(notes[0].note.market_id, capped_market);
+        assert_eq!(notes[0].note.payout, Uint128::from(5_000u128));
+        assert_eq!(notes[1].note.market_id, MARKET_ID);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn fixed_expiry_notes_share_maturity() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+
+        let expiry = chain.block_info()?.time.plus_seconds(3600);
+        bond.create_market(
+            None,
+            None,
+            Uint128::MAX,
+            false,
+            None,
+            AssetInfoUnchecked::native(BOND_TOKEN),
+            Terms {
+                vesting: VestingMode::FixedExpiry(expiry),
+                ..terms()?
+            },
+            None,
+        )?;
+        let expiry_market = MARKET_ID + 1;
+
+        let max_price = Decimal256::from_str("5")?;
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            expiry_market,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        chain.wait_seconds(600)?;
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            expiry_market,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+
+        // Both notes vest at the same expiry
+        let notes = bond.notes(None, chain.sender().to_string(), None)?;
+        for note in notes.iter().map(|n| &n.note) {
+            assert_eq!(note.last_time.plus_seconds(note.vesting_time_left), expiry);
+        }
+
+        chain.wait_seconds(3000)?;
+
+        let err = bond
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                expiry_market,
+                max_price,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("has expired"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn fixed_terms_must_vest() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+
+        let err = bond
+            .update_terms(
+                MARKET_ID,
+                Terms {
+                    vesting: VestingMode::FixedTerm(0),
+                    ..terms()?
+                },
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("must vest for at least one second"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn cw20_quote_tokens_deposit_through_receive() -> anyhow::Result<()> {
+        let (_shogun, bond, treasury) = init()?;
+        let chain = bond.get_chain().clone();
+
+        let token = Cw20Base::new("quote_token", chain.clone());
+        token.upload()?;
+        token.instantiate(
+            &cw20_base::msg::InstantiateMsg {
+                name: "Quote token".to_string(),
+                symbol: "QUOTE".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: chain.sender().to_string(),
+                    amount: 100_000u128.into(),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            None,
+            None,
+        )?;
+
+        bond.create_market(
+            None,
+            None,
+            Uint128::MAX,
+            false,
+            None,
+            AssetInfoUnchecked::cw20(token.address()?),
+            terms()?,
+            None,
+        )?;
+        let cw20_market = MARKET_ID + 1;
+
+        // Native funds are refused by cw20 markets
+        let err = bond
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                cw20_market,
+                Decimal256::from_str("5")?,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("is quoted in"));
+
+        token.execute(
+            &cw20_base::msg::ExecuteMsg::Send {
+                contract: bond.address()?.to_string(),
+                amount: 10_000u128.into(),
+                msg: to_json_binary(&ReceiveMsg::Deposit {
+                    market_id: cw20_market,
+                    max_price: Decimal256::from_str("5")?,
+                    depositor: chain.sender().to_string(),
+                    referrer: None,
+                    proof: None,
+                    callback: None,
+                })?,
+            },
+            None,
+        )?;
+
+        assert_cw20_balance(
+            chain.clone(),
+            token.address()?.to_string(),
+            10_000,
+            treasury.address(),
+        )?;
+        let notes = bond.notes(None, chain.sender().to_string(), None)?;
+        assert_eq!(notes[0].note.market_id, cw20_market);
+        assert_eq!(notes[0].note.payout, Uint128::from(5_000u128));
+
+        // Deposits can't be made with another token
+        let err = bond
+            .execute(
+                &ExecuteMsg::Receive(Cw20ReceiveMsg {
+                    sender: chain.sender().to_string(),
+                    amount: 10_000u128.into(),
+                    msg: to_json_binary(&ReceiveMsg::Deposit {
+                        market_id: cw20_market,
+                        max_price: Decimal256::from_str("5")?,
+                        depositor: chain.sender().to_string(),
+                        referrer: None,
+                        proof: None,
+                        callback: None,
+                    })?,
+                }),
+                None,
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("is quoted in"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn lp_deposits_are_valued_by_the_calculator() -> anyhow::Result<()> {
+        let (shogun, bond, treasury) = init()?;
+        let chain = bond.get_chain().clone();
+
+        let lp_token = Cw20Base::new("lp_token", chain.clone());
+        lp_token.upload()?;
+        lp_token.instantiate(
+            &cw20_base::msg::InstantiateMsg {
+                name: "Pool shares".to_string(),
+                symbol: "SHARES".to_string(),
+                decimals: 6,
+                initial_balances: vec![Cw20Coin {
+                    address: chain.sender().to_string(),
+                    amount: 1_000_000u128.into(),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            None,
+            None,
+        )?;
+
+        let pool = MockPool::new("pool", chain.clone());
+        pool.upload()?;
+        pool.instantiate(
+            &mock_pool::msg::InstantiateMsg {
+                lp_token: lp_token.address()?.to_string(),
+                assets: [shogun.staking.config()?.ohm_denom, BOND_TOKEN.to_string()],
+                decimals: [6, 6],
+                reserves: [4_000_000u128.into(), 1_000_000u128.into()],
+            },
+            None,
+            None,
+        )?;
+
+        let calculator = BondingCalculator::new("calculator", chain.clone());
+        calculator.upload()?;
+        calculator.instantiate(&bonding_calculator::msg::InstantiateMsg {}, None, None)?;
+
+        // 2 * sqrt(4_000_000 * 1_000_000) for 1_000_000 shares
+        assert_eq!(
+            calculator.total_value(pool.address()?.to_string())?,
+            Uint128::from(4_000_000u128)
+        );
+        assert_eq!(
+            calculator.valuation(10_000u128.into(), pool.address()?.to_string())?,
+            Uint128::from(40_000u128)
+        );
+
+        bond.create_market(
+            None,
+            Some(CalculatorConfig {
+                calculator: calculator.address()?.to_string(),
+                pool: pool.address()?.to_string(),
+            }),
+            Uint128::MAX,
+            false,
+            None,
+            AssetInfoUnchecked::cw20(lp_token.address()?),
+            terms()?,
+            None,
+        )?;
+        let lp_market = MARKET_ID + 1;
+
+        assert_eq!(
+            bond.quote_value(10_000u128.into(), lp_market)?,
+            Uint128::from(40_000u128)
+        );
+        let expected_payout = bond.payout_for(lp_market, 40_000u128.into())?;
+
+        lp_token.execute(
+            &cw20_base::msg::ExecuteMsg::Send {
+                contract: bond.address()?.to_string(),
+                amount: 10_000u128.into(),
+                msg: to_json_binary(&ReceiveMsg::Deposit {
+                    market_id: lp_market,
+                    max_price: Decimal256::from_str("5")?,
+                    depositor: chain.sender().to_string(),
+                    referrer: None,
+                    proof: None,
+                    callback: None,
+                })?,
+            },
+            None,
+        )?;
+
+        let notes = bond.notes(None, chain.sender().to_string(), None)?;
+        assert_eq!(notes[0].note.payout, expected_payout);
+        assert_eq!(
+            bond.market(lp_market)?.market.purchased,
+            Uint128::from(10_000u128)
+        );
+        assert_cw20_balance(
+            chain.clone(),
+            lp_token.address()?.to_string(),
+            10_000,
+            treasury.address(),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn lp_payouts_ignore_the_decimals_of_the_lp_token() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let chain = bond.get_chain().clone();
+
+        let lp_token = Cw20Base::new("lp_token", chain.clone());
+        lp_token.upload()?;
+        lp_token.instantiate(
+            &cw20_base::msg::InstantiateMsg {
+                name: "Pool shares".to_string(),
+                symbol: "SHARES".to_string(),
+                decimals: 18,
+                initial_balances: vec![Cw20Coin {
+                    address: chain.sender().to_string(),
+                    amount: 1_000_000_000_000_000_000u128.into(),
+                }],
+                mint: None,
+                marketing: None,
+            },
+            None,
+            None,
+        )?;
+
+        // 4 OHM against 1 token with 18 decimals
+        let pool = MockPool::new("pool", chain.clone());
+        pool.upload()?;
+        pool.instantiate(
+            &mock_pool::msg::InstantiateMsg {
+                lp_token: lp_token.address()?.to_string(),
+                assets: [shogun.staking.config()?.ohm_denom, BOND_TOKEN.to_string()],
+                decimals: [6, 18],
+                reserves: [4_000_000u128.into(), 1_000_000_000_000_000_000u128.into()],
+            },
+            None,
+            None,
+        )?;
+
+        let calculator = BondingCalculator::new("calculator", chain.clone());
+        calculator.upload()?;
+        calculator.instantiate(&bonding_calculator::msg::InstantiateMsg {}, None, None)?;
+
+        bond.create_market(
+            None,
+            Some(CalculatorConfig {
+                calculator: calculator.address()?.to_string(),
+                pool: pool.address()?.to_string(),
+            }),
+            Uint128::MAX,
+            false,
+            None,
+            AssetInfoUnchecked::cw20(lp_token.address()?),
+            terms()?,
+            None,
+        )?;
+        let lp_market = MARKET_ID + 1;
+
+        // 1% of the shares are worth 0.04 OHM, bought at 2 per OHM
+        let shares = Uint128::new(10_000_000_000_000_000);
+        assert_eq!(
+            bond.quote_value(shares, lp_market)?,
+            Uint128::from(40_000u128)
+        );
+        assert_eq!(
+            bond.payout_for(lp_market, 40_000u128.into())?,
+            Uint128::from(20_000u128)
+        );
+
+        lp_token.execute(
+            &cw20_base::msg::ExecuteMsg::Send {
+                contract: bond.address()?.to_string(),
+                amount: shares,
+                msg: to_json_binary(&ReceiveMsg::Deposit {
+                    market_id: lp_market,
+                    max_price: Decimal256::from_str("2")?,
+                    depositor: chain.sender().to_string(),
+                    referrer: None,
+                    proof: None,
+                    callback: None,
+                })?,
+            },
+            None,
+        )?;
+
+        let notes = bond.notes(None, chain.sender().to_string(), None)?;
+        assert_eq!(notes[0].note.payout, Uint128::from(20_000u128));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn tuned_markets_sell_out_by_the_conclusion() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+
+        let tuning = TuningConfig {
+            conclusion: chain.block_info()?.time.plus_seconds(10_000),
+            deposit_interval: 1_000,
+            tune_interval: 1_000,
+        };
+        bond.create_market(
+            None,
+            None,
+            100_000u128.into(),
+            false,
+            None,
+            AssetInfoUnchecked::native(BOND_TOKEN),
+            terms()?,
+            Some(tuning.clone()),
+        )?;
+        let tuned_market = MARKET_ID + 1;
+
+        let first_tuning = bond.market_tuning(tuned_market)?;
+        assert_eq!(first_tuning.config, tuning);
+        assert_eq!(
+            first_tuning.next_tune,
+            first_tuning.last_tune.plus_seconds(1_000)
+        );
+        bond.market_tuning(MARKET_ID).unwrap_err();
+
+        // The market isn't tuned before the tune interval
+        let max_price = Decimal256::from_str("5")?;
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            tuned_market,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        assert_eq!(bond.market_tuning(tuned_market)?, first_tuning);
+        assert_eq!(bond.terms(tuned_market)?, terms()?);
+
+        chain.wait_seconds(1_000)?;
+        // The market is tuned before this deposit is priced, with the capacity it had
+        let capacity = bond.market(tuned_market)?.market.capacity.u128();
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            tuned_market,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+
+        // Max size deposits every deposit interval sell the capacity left by the conclusion
+        let market_tuning = bond.market_tuning(tuned_market)?;
+        assert!(market_tuning.last_tune >= first_tuning.next_tune);
+        let time_remaining = tuning.conclusion.seconds() - market_tuning.last_tune.seconds();
+        let expected_max_payout = capacity * 1_000 / time_remaining as u128;
+        let max_payout = bond.max_payout(tuned_market)?.u128();
+        assert!(max_payout.abs_diff(expected_max_payout) * 100 <= expected_max_payout);
+
+        chain.wait_seconds(10_000)?;
+        let err = bond
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                tuned_market,
+                max_price,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("has concluded"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn tuning_keeps_active_adjustments() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+
+        bond.create_market(
+            None,
+            None,
+            100_000u128.into(),
+            false,
+            None,
+            AssetInfoUnchecked::native(BOND_TOKEN),
+            terms()?,
+            Some(TuningConfig {
+                conclusion: chain.block_info()?.time.plus_seconds(10_000),
+                deposit_interval: 1_000,
+                tune_interval: 1_000,
+            }),
+        )?;
+        let tuned_market = MARKET_ID + 1;
+
+        // The adjustment is in progress but can't move before its buffer
+        bond.update_adjustment(
+            Some(true),
+            Some(1_000_000),
+            tuned_market,
+            Some(Decimal256::one()),
+            Some(Decimal256::from_str("2000")?),
+        )?;
+        let adjustment = bond.adjustment(tuned_market)?;
+
+        chain.wait_seconds(1_000)?;
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            tuned_market,
+            Decimal256::from_str("5")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+
+        // Only the max payout was tuned
+        assert!(bond.market_tuning(tuned_market)?.last_tune > adjustment.last_time);
+        assert_eq!(bond.adjustment(tuned_market)?, adjustment);
+        let tuned_terms = bond.terms(tuned_market)?;
+        assert_eq!(tuned_terms.control_variable, terms()?.control_variable);
+        assert_ne!(tuned_terms.max_payout, terms()?.max_payout);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn dutch_auction_price_decays_and_jumps() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+
+        let params = AuctionParams {
+            initial_price: Decimal256::from_str("4")?,
+            decay_speed: Decimal256::from_str("0.1")?,
+            deposit_interval: 1_000,
+        };
+        let auction_terms = Terms {
+            minimum_price: Decimal256::from_str("1")?,
+            pricing: PricingModel::SequentialDutchAuction(params.clone()),
+            ..terms()?
+        };
+        bond.create_market(
+            None,
+            None,
+            Uint128::MAX,
+            false,
+            None,
+            AssetInfoUnchecked::native(BOND_TOKEN),
+            auction_terms.clone(),
+            None,
+        )?;
+        let auction_market = MARKET_ID + 1;
+        assert!(bond.bond_price(auction_market)? <= params.initial_price);
+
+        // 10% of the initial price is lost every deposit interval
+        chain.wait_seconds(5_000)?;
+        let decayed_price = bond.bond_price(auction_market)?;
+        assert!(decayed_price <= Decimal256::from_str("2")?);
+        assert!(decayed_price > Decimal256::from_str("1.9")?);
+
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            auction_market,
+            Decimal256::from_str("2")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        assert!(bond.bond_price(auction_market)? > decayed_price);
+
+        chain.wait_seconds(100_000)?;
+        assert_eq!(
+            bond.bond_price(auction_market)?,
+            auction_terms.minimum_price
+        );
+
+        let err = bond
+            .update_terms(
+                auction_market,
+                Terms {
+                    minimum_price: Decimal256::from_str("5")?,
+                    ..auction_terms.clone()
+                },
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("auction must decay"));
+
+        // The auction goes on above the new minimum price
+        bond.update_terms(
+            auction_market,
+            Terms {
+                minimum_price: Decimal256::from_str("1.5")?,
+                ..auction_terms.clone()
+            },
+        )?;
+        assert_eq!(
+            bond.bond_price(auction_market)?,
+            Decimal256::from_str("1.5")?
+        );
+
+        // New auction parameters restart the auction
+        bond.update_terms(
+            auction_market,
+            Terms {
+                pricing: PricingModel::SequentialDutchAuction(AuctionParams {
+                    initial_price: Decimal256::from_str("3")?,
+                    ..params
+                }),
+                ..auction_terms
+            },
+        )?;
+        let restarted_price = bond.bond_price(auction_market)?;
+        assert!(restarted_price <= Decimal256::from_str("3")?);
+        assert!(restarted_price > Decimal256::from_str("2.9")?);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn dao_fee_is_minted_on_deposits() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let dao = chain.init_account(vec![])?;
+
+        // The fee needs an address to be minted to
+        bond.update_config(None, Some(Decimal256::percent(10)), None, None, None, None)
+            .unwrap_err();
+        bond.update_config(
+            Some(dao.address().to_string()),
+            Some(Decimal256::percent(10)),
+            None,
+            None,
+            None,
+            None,
+        )?;
+        assert_eq!(bond.config()?.dao_fee, Decimal256::percent(10));
+
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            Decimal256::from_str("2")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+
+        assert_balance(
+            chain.clone(),
+            shogun.staking.config()?.ohm_denom,
+            500,
+            dao.address().to_string(),
+        )?;
+        assert_eq!(
+            bond.note(0, chain.sender().to_string())?.payout,
+            Uint128::new(5_000)
+        );
+        // The fee is debt of the market like the payout
+        assert_eq!(
+            bond.market(MARKET_ID)?.market.total_debt,
+            Uint128::new(5_500)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn referrers_earn_rewards_on_deposits() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let frontend = chain.init_account(vec![])?;
+        let max_price = Decimal256::from_str("2")?;
+
+        // Only whitelisted frontends can refer deposits
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            max_price,
+            None,
+            Some(frontend.address().to_string()),
+            &coins(10_000, BOND_TOKEN),
+        )
+        .unwrap_err();
+
+        bond.set_referrer(Some(Decimal256::percent(5)), frontend.address().to_string())?;
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            max_price,
+            None,
+            Some(frontend.address().to_string()),
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        assert_eq!(
+            bond.referral_rewards(frontend.address().to_string())?,
+            Uint128::new(250)
+        );
+
+        bond.call_as(&frontend).claim_referral_rewards()?;
+        assert_balance(
+            chain.clone(),
+            shogun.staking.config()?.ohm_denom,
+            250,
+            frontend.address().to_string(),
+        )?;
+        assert!(bond
+            .referral_rewards(frontend.address().to_string())?
+            .is_zero());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn private_markets_check_the_allow_list() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let partner = chain.sender().to_string();
+        let outsider = chain.init_account(vec![coin(100_000, BOND_TOKEN)])?;
+        let max_price = Decimal256::from_str("2")?;
+
+        // Tree of two leaves, each one is the proof of the other
+        let partner_leaf: [u8; 32] = Sha256::digest(partner.as_bytes()).into();
+        let other_leaf: [u8; 32] = Sha256::digest(b"inj1otherpartner").into();
+        let mut pair = [partner_leaf, other_leaf];
+        pair.sort_unstable();
+        let merkle_root = hex::encode(Sha256::digest(pair.concat()));
+        let proof = vec![hex::encode(other_leaf)];
+
+        bond.create_market(
+            Some(AllowList {
+                merkle_root,
+                cap: Some(15_000u128.into()),
+            }),
+            None,
+            Uint128::MAX,
+            false,
+            None,
+            AssetInfoUnchecked::native(BOND_TOKEN),
+            terms()?,
+            None,
+        )?;
+        let private_market = MARKET_ID + 1;
+
+        assert!(bond.is_allowed(partner.clone(), private_market, proof.clone())?);
+        assert!(!bond.is_allowed(outsider.address(), private_market, proof.clone())?);
+        assert!(bond.is_allowed(outsider.address(), MARKET_ID, vec![])?);
+
+        let err = bond
+            .call_as(&outsider)
+            .deposit(
+                None,
+                outsider.address(),
+                private_market,
+                max_price,
+                Some(proof.clone()),
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("isn't allowed"));
+
+        bond.deposit(
+            None,
+            partner.clone(),
+            private_market,
+            max_price,
+            Some(proof.clone()),
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        // The partner can't deposit more than its cap
+        let err = bond
+            .deposit(
+                None,
+                partner,
+                private_market,
+                max_price,
+                Some(proof),
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("reached its cap"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn adjustment_schedule_advances_on_deposits() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let chain = bond.get_chain().clone();
+        let now = chain.block_info()?.time;
+        let max_price = Decimal256::from_str("100")?;
+
+        let steps = vec![
+            AdjustmentStep {
+                start: now,
+                add: true,
+                rate: Decimal256::from_str("100")?,
+                target: Decimal256::from_str("1100")?,
+                buffer: 0,
+            },
+            AdjustmentStep {
+                start: now,
+                add: false,
+                rate: Decimal256::from_str("50")?,
+                target: Decimal256::from_str("1000")?,
+                buffer: 0,
+            },
+        ];
+        // The steps must be in start order
+        bond.schedule_adjustments(
+            MARKET_ID,
+            vec![
+                AdjustmentStep {
+                    start: now.plus_seconds(10),
+                    ..steps[0].clone()
+                },
+                steps[1].clone(),
+            ],
+        )
+        .unwrap_err();
+        bond.schedule_adjustments(MARKET_ID, steps.clone())?;
+        assert_eq!(bond.adjustment_schedule(MARKET_ID)?, steps);
+
+        let mut control_variables = vec![];
+        for _ in 0..3 {
+            bond.deposit(
+                None,
+                chain.sender().to_string(),
+                MARKET_ID,
+                max_price,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )?;
+            control_variables.push(bond.terms(MARKET_ID)?.control_variable);
+        }
+
+        // The first step reaches its target, then the second one takes over
+        assert_eq!(
+            control_variables,
+            vec![
+                Decimal256::from_str("1100")?,
+                Decimal256::from_str("1050")?,
+                Decimal256::from_str("1000")?,
+            ]
+        );
+        assert!(bond.adjustment_schedule(MARKET_ID)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn deposits_and_redemptions_are_recorded() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let chain = bond.get_chain().clone();
+        let sender = chain.sender().to_string();
+
+        assert_eq!(
+            bond.market_stats()?,
+            vec![MarketStatsResponse {
+                market_id: MARKET_ID,
+                total_principle: Uint128::zero(),
+                total_payout: Uint128::zero(),
+                average_price: Decimal256::zero(),
+            }]
+        );
+
+        bond.deposit(
+            None,
+            sender.clone(),
+            MARKET_ID,
+            Decimal256::from_str("2")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        let deposit_time = chain.block_info()?.time;
+        chain.wait_seconds(3600)?;
+        bond.redeem(None, sender.clone(), false)?;
+
+        let history = bond.deposit_history(sender.clone(), None, None)?;
+        assert_eq!(history.len(), 2);
+        assert_eq!(
+            history[0].entry,
+            HistoryEntry {
+                event: HistoryEvent::Deposit {
+                    market_id: MARKET_ID,
+                    note_id: Some(0),
+                    amount: 10_000u128.into(),
+                    price: Decimal256::from_str("2")?,
+                    payout: 5_000u128.into(),
+                },
+                timestamp: deposit_time,
+            }
+        );
+        assert_eq!(
+            history[1].entry.event,
+            HistoryEvent::Redeem {
+                payout: 5_000u128.into(),
+                stake: false,
+            }
+        );
+        assert_eq!(
+            bond.deposit_history(sender, None, Some(history[0].id))?,
+            vec![history[1].clone()]
+        );
+
+        assert_eq!(
+            bond.market_stats()?,
+            vec![MarketStatsResponse {
+                market_id: MARKET_ID,
+                total_principle: 10_000u128.into(),
+                total_payout: 5_000u128.into(),
+                average_price: Decimal256::from_str("2")?,
+            }]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn keepers_redeem_for_opted_in_bonders() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+        let bonder = chain.init_account(vec![])?;
+        let lazy_bonder = chain.init_account(vec![])?;
+        let keeper = chain.init_account(vec![])?;
+
+        for depositor in [&bonder, &lazy_bonder] {
+            bond.deposit(
+                None,
+                depositor.address().to_string(),
+                MARKET_ID,
+                Decimal256::from_str("2")?,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )?;
+        }
+        bond.call_as(&bonder)
+            .set_auto_redeem(false, Decimal256::percent(1))?;
+        assert_eq!(
+            bond.auto_redeemers(None, None)?,
+            vec![AutoRedeemerResponse {
+                address: bonder.address().to_string(),
+                preference: AutoRedeem {
+                    stake: false,
+                    tip: Decimal256::percent(1),
+                },
+            }]
+        );
+
+        chain.wait_seconds(3600)?;
+        bond.call_as(&keeper).redeem_for(
+            vec![
+                bonder.address().to_string(),
+                lazy_bonder.address().to_string(),
+            ],
+            false,
+        )?;
+
+        // The lazy bonder didn't opt in, its note is left untouched
+        assert_balance(
+            chain.clone(),
+            ohm_denom.clone(),
+            4_950,
+            bonder.address().to_string(),
+        )?;
+        assert_balance(
+            chain.clone(),
+            ohm_denom.clone(),
+            50,
+            keeper.address().to_string(),
+        )?;
+        assert_balance(
+            chain.clone(),
+            ohm_denom,
+            0,
+            lazy_bonder.address().to_string(),
+        )?;
+        assert_eq!(
+            bond.notes(None, lazy_bonder.address().to_string(), None)?
+                .len(),
+            1
+        );
+
+        // Nothing is left to redeem
+        bond.call_as(&keeper)
+            .redeem_for(vec![bonder.address().to_string()], false)
+            .unwrap_err();
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn guardian_pauses_deposits() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let guardian = chain.init_account(vec![])?;
+
+        bond.update_config(
+            None,
+            None,
+            Some(guardian.address().to_string()),
+            None,
+            None,
+            None,
+        )?;
+
+        bond.call_as(&guardian).pause(vec![Operation::Deposit])?;
+        assert_eq!(
+            bond.pause_status()?,
+            PauseStatus {
+                deposit: true,
+                redeem: false,
+            }
+        );
+
+        let max_price = Decimal256::from_str("2")?;
+        let err = bond
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                MARKET_ID,
+                max_price,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Deposit is paused"));
+
+        // Only the owner can unpause
+        bond.call_as(&guardian)
+            .unpause(vec![Operation::Deposit])
+            .unwrap_err();
+        bond.unpause(vec![Operation::Deposit])?;
+
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+
+        // Once removed, the guardian can't pause anymore
+        bond.remove_guardian()?;
+        assert_eq!(bond.config()?.guardian, None);
+        bond.call_as(&guardian)
+            .pause(vec![Operation::Deposit])
+            .unwrap_err();
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn governance_sudo_controls() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let new_owner = chain.init_account(vec![])?;
+        let recovery = chain.init_account(vec![])?;
+        let bond_addr = bond.address()?;
+
+        wasm_sudo(
+            &chain,
+            &bond_addr,
+            &SudoMsg::UpdateOwner {
+                owner: new_owner.address().to_string(),
+            },
+        )?;
+        assert_eq!(
+            bond.ownership()?.owner,
+            Some(Addr::unchecked(new_owner.address()))
+        );
+
+        wasm_sudo(
+            &chain,
+            &bond_addr,
+            &SudoMsg::Pause {
+                operations: vec![Operation::Deposit],
+            },
+        )?;
+        let max_price = Decimal256::from_str("2")?;
+        let err = bond
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                MARKET_ID,
+                max_price,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Deposit is paused"));
+
+        wasm_sudo(
+            &chain,
+            &bond_addr,
+            &SudoMsg::Unpause {
+                operations: vec![Operation::Deposit],
+            },
+        )?;
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            max_price,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+
+        // The pending payouts are held by the bond contract
+        let payout = bond.note(0, chain.sender().to_string())?.payout;
+        wasm_sudo(
+            &chain,
+            &bond_addr,
+            &SudoMsg::SweepFunds {
+                recipient: recovery.address().to_string(),
+            },
+        )?;
+        assert_balance(
+            chain.clone(),
+            shogun.staking.config()?.ohm_denom,
+            payout.u128(),
+            recovery.address().to_string(),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn oracle_prices_the_discount() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let chain = bond.get_chain().clone();
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+
+        let oracle = Oracle::new("oracle", chain.clone());
+        oracle.upload()?;
+        oracle.instantiate(
+            &oracle::msg::InstantiateMsg {
+                owner: chain.sender().to_string(),
+                base_asset: BOND_TOKEN.to_string(),
+                guardian: None,
+            },
+            None,
+            None,
+        )?;
+        oracle.execute(
+            &oracle::msg::ExecuteMsg::RegisterFeeder {
+                asset: ohm_denom.clone(),
+                feeder: chain.sender().to_string(),
+            },
+            None,
+        )?;
+        oracle.execute(
+            &oracle::msg::ExecuteMsg::FeedPrice {
+                prices: vec![(ohm_denom.clone(), Decimal256::from_str("2.5")?)],
+            },
+            None,
+        )?;
+
+        // Markets without an oracle have no market price
+        bond.market_price(MARKET_ID).unwrap_err();
+
+        bond.create_market(
+            None,
+            None,
+            Uint128::MAX,
+            false,
+            Some(OracleConfig {
+                source: OracleSource::Contract {
+                    address: oracle.address()?.to_string(),
+                },
+                require_discount: true,
+            }),
+            AssetInfoUnchecked::native(BOND_TOKEN),
+            terms()?,
+            None,
+        )?;
+        let oracle_market = MARKET_ID + 1;
+
+        assert_eq!(
+            bond.market_price(oracle_market)?,
+            Decimal256::from_str("2.5")?
+        );
+        assert_eq!(
+            bond.bond_discount(oracle_market)?,
+            BondDiscountResponse {
+                discount: Decimal256::from_str("0.2")?,
+                negative: false,
+            }
+        );
+
+        // OHM is now cheaper on the market than through the bond
+        oracle.execute(
+            &oracle::msg::ExecuteMsg::FeedPrice {
+                prices: vec![(ohm_denom, Decimal256::from_str("1.6")?)],
+            },
+            None,
+        )?;
+        assert_eq!(
+            bond.bond_discount(oracle_market)?,
+            BondDiscountResponse {
+                discount: Decimal256::from_str("0.25")?,
+                negative: true,
+            }
+        );
+        let err = bond
+            .deposit(
+                None,
+                chain.sender().to_string(),
+                oracle_market,
+                Decimal256::from_str("2")?,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("priced above the market"));
+
+        // The other market doesn't check the discount
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            Decimal256::from_str("2")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn payouts_are_locked_in_the_lock_contract() -> anyhow::Result<()> {
+        let (shogun, bond, _treasury) = init()?;
+        let chain = bond.get_chain().clone();
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+        let sender = chain.sender().to_string();
+
+        let lock = LockContract::new("lock", chain.clone());
+        lock.upload()?;
+        lock.instantiate(
+            &lock_contract::msg::InstantiateMsg {
+                owner: None,
+                accepted_tokens: vec![AcceptedTokenUnchecked {
+                    asset: AssetInfoUnchecked::native(&ohm_denom),
+                    deposit_fee: Fee::Fixed(1_000u128.into()),
+                }],
+                guardian: None,
+            },
+            None,
+            None,
+        )?;
+
+        let lock_terms = Terms {
+            lock_payout: true,
+            ..terms()?
+        };
+        // The payouts need a lock contract to go to
+        bond.update_terms(MARKET_ID, lock_terms.clone())
+            .unwrap_err();
+        bond.update_config(
+            None,
+            None,
+            None,
+            Some(lock.address()?.to_string()),
+            None,
+            None,
+        )?;
+        bond.update_terms(MARKET_ID, lock_terms)?;
+
+        // The depositor is quoted the price of the 4_000 locked after the fee, not of the
+        // 5_000 payout
+        let err = bond
+            .deposit(
+                None,
+                sender.clone(),
+                MARKET_ID,
+                Decimal256::from_str("2")?,
+                None,
+                None,
+                &coins(10_000, BOND_TOKEN),
+            )
+            .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("Slippage limit: more than max price"));
+        bond.deposit(
+            None,
+            sender.clone(),
+            MARKET_ID,
+            Decimal256::from_str("2.5")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        let history = bond.deposit_history(sender.clone(), None, None)?;
+        assert_eq!(
+            history[0].entry.event,
+            HistoryEvent::Deposit {
+                market_id: MARKET_ID,
+                note_id: None,
+                amount: 10_000u128.into(),
+                price: Decimal256::from_str("2.5")?,
+                payout: 4_000u128.into(),
+            }
+        );
+
+        // No note is kept by the depository, the payout vests in the lock contract
+        // after its deposit fee
+        assert!(bond.notes(None, sender.clone(), None)?.is_empty());
+        let locks = lock.locks_for_address(sender.clone(), None, None)?;
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].recipient, sender);
+        assert_eq!(
+            locks[0].asset,
+            cw_asset::AssetUnchecked::native(&ohm_denom, 4_000u128)
+        );
+        match &locks[0].lock {
+            DepositLock::Linear(LinearLock {
+                per_second_vesting, ..
+            }) => assert_eq!(
+                *per_second_vesting,
+                Decimal256::from_ratio(4_000u128, 3600u128)
+            ),
+            other => panic!("Unexpected lock {other:?}"),
+        }
+        assert_balance(
+            chain.clone(),
+            ohm_denom.clone(),
+            1_000,
+            lock.address()?.to_string(),
+        )?;
+
+        chain.wait_seconds(3600)?;
+        lock.unlock(locks[0].id, sender.clone())?;
+        assert_balance(chain.clone(), ohm_denom, 4_000, sender)?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn deposit_callbacks_are_sent_to_the_sender() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let chain = bond.get_chain().clone();
+
+        // The sender isn't a contract, it can't handle the callback
+        bond.deposit(
+            Some(to_json_binary(&"bonded")?),
+            chain.sender().to_string(),
+            MARKET_ID,
+            Decimal256::from_str("2")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )
+        .unwrap_err();
+        assert!(bond
+            .notes(None, chain.sender().to_string(), None)?
+            .is_empty());
+
+        bond.deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            Decimal256::from_str("2")?,
+            None,
+            None,
+            &coins(10_000, BOND_TOKEN),
+        )?;
+        assert_eq!(bond.notes(None, chain.sender().to_string(), None)?.len(), 1);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn payouts_account_for_the_quote_decimals() -> anyhow::Result<()> {
+        let (_shogun, bond, _treasury) = init()?;
+        let mut chain = bond.get_chain().clone();
+        let bond_token = AssetInfoUnchecked::native(BOND_TOKEN);
+
+        // OHM and the bond token both have 6 decimals, the price applies to the raw amounts
+        assert_eq!(bond.decimals(bond_token.clone())?, 6);
+        assert_eq!(bond.payout_for(MARKET_ID, 10_000u128.into())?.u128(), 5_000);
+
+        // 10_000 units of a token with 8 decimals are worth 100 times less OHM
+        bond.set_decimals(bond_token.clone(), Some(8))?;
+        assert_eq!(bond.decimals(bond_token.clone())?, 8);
+        assert_eq!(bond.payout_for(MARKET_ID, 10_000u128.into())?.u128(), 50);
+
+        bond.set_decimals(bond_token.clone(), Some(19)).unwrap_err();
+        let user = chain.init_account(vec![])?;
+        bond.call_as(&user)
+            .set_decimals(bond_token.clone(), Some(6))
+            .unwrap_err();
+
+        // Unknown to the exchange module, the bond token can't be priced without its decimals
+        bond.set_decimals(bond_token.clone(), None)?;
+        bond.decimals(bond_token.clone()).unwrap_err();
+        bond.payout_for(MARKET_ID, 10_000u128.into()).unwrap_err();
+        bond.create_market(
+            None,
+            None,
+            Uint128::MAX,
+            false,
+            None,
+            bond_token,
+            terms()?,
+            None,
+        )
+        .unwrap_err();
+
+        Ok(())
+    }
 }
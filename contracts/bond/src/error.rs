@@ -1,6 +1,9 @@
 use cosmwasm_std::{Addr, Response, StdError, Binary};
+use cw_asset::AssetInfo;
 use thiserror::Error;
 
+use crate::state::Operation;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -24,10 +27,114 @@ pub enum ContractError {
     #[error("You need to send exactly one coin with this function with denom {0}")]
     ReceiveOneCoin(String),
 
+    #[error(transparent)]
+    AssetError(#[from] cw_asset::AssetError),
+
     #[error(transparent)]
     ConversionOverflowError(#[from] cosmwasm_std::ConversionOverflowError),
+
+    #[error(transparent)]
+    DecimalRangeExceeded(#[from] cosmwasm_std::DecimalRangeExceeded),
+
+    #[error(transparent)]
+    CheckedFromRatio(#[from] cosmwasm_std::CheckedFromRatioError),
+
+    #[error(transparent)]
+    Ownership(#[from] cw_ownable::OwnershipError),
+
+    #[error(transparent)]
+    ParseReply(#[from] cw_utils::ParseReplyError),
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
+    #[error("{operation:?} is paused")]
+    Paused { operation: Operation },
+
+    #[error("Market {market_id} doesn't exist")]
+    MarketNotFound { market_id: u64 },
+
+    #[error("Market {market_id} is closed")]
+    MarketClosed { market_id: u64 },
+
+    #[error("Market {market_id} doesn't have enough capacity left")]
+    MarketCapacityExceeded { market_id: u64 },
+
+    #[error("Market {market_id} has expired")]
+    MarketExpired { market_id: u64 },
+
+    #[error("Market {market_id} has concluded")]
+    MarketConcluded { market_id: u64 },
+
+    #[error("Market {market_id} isn't tuned")]
+    NoTuning { market_id: u64 },
+
+    #[error("The conclusion must be in the future and the tune interval at least the deposit interval")]
+    InvalidTuning {},
+
+    #[error("The auction must decay by at most 100% per deposit interval from an initial price above the minimum")]
+    InvalidPricing {},
+
+    #[error("Market {market_id} is quoted in {quote_token}")]
+    WrongQuoteToken { market_id: u64, quote_token: AssetInfo },
+
+    #[error("The DAO fee must be below 100% and needs a DAO address")]
+    InvalidDaoFee {},
+
+    #[error("{referrer} isn't a whitelisted referrer")]
+    NotReferrer { referrer: String },
+
+    #[error("The referral rate must be below 100%")]
+    InvalidReferralRate {},
+
+    #[error("The depositor isn't allowed in market {market_id}")]
+    NotAllowed { market_id: u64 },
+
+    #[error("The depositor reached its cap in market {market_id}")]
+    AllowListCapExceeded { market_id: u64 },
+
+    #[error("The merkle root must be a hex encoded sha256 hash")]
+    InvalidMerkleRoot {},
+
+    #[error("The merkle proof must be hex encoded sha256 hashes")]
+    InvalidMerkleProof {},
+
+    #[error("The steps of an adjustment schedule must be in start order")]
+    InvalidSchedule {},
+
+    #[error("The tip must be below 100%")]
+    InvalidTip {},
+
+    #[error("No oracle is configured")]
+    NoOracle {},
+
+    #[error("The oracle has no price for this pair")]
+    NoOraclePrice {},
+
+    #[error("The bond is priced above the market")]
+    NegativeDiscount {},
+
+    #[error("Note {note_id} doesn't exist")]
+    NoteNotFound { note_id: u64 },
+
+    #[error("No note NFT contract is configured")]
+    NoNoteNft {},
+
+    #[error("No lock contract is configured")]
+    NoLockContract {},
+
+    #[error("Fixed term notes must vest for at least one second")]
+    InvalidVesting {},
+
+    #[error("The decimals of {asset} are unknown, they have to be set by the owner")]
+    UnknownDecimals { asset: AssetInfo },
+
+    #[error("Decimals can't be greater than 18")]
+    InvalidDecimals {},
+
+    #[error("Invalid reply id")]
+    InvalidReplyId {},
+
+    #[error(transparent)]
+    Migrate(#[from] msgs::MigrateError),
 }
 
 pub type ContractResult<T = Response> = Result<T, ContractError>;
@@ -1,13 +1,30 @@
+use bond_note::msg::NoteMetadata;
 use cosmwasm_std::{
-    ensure, to_json_binary, Addr, BankMsg, Coin, CosmosMsg, Decimal256, Deps, DepsMut, Env,
-    MessageInfo, Response, StdError, Uint128, Uint256,
+    ensure, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Decimal256, Deps,
+    DepsMut, Env, MessageInfo, Order, Response, StdError, StdResult, SubMsg, Timestamp, Uint128,
+    Uint256, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked, AssetUnchecked};
+use lock_contract::state::{
+    deposit::{locks::LinearLock, DepositLock},
+    Fee,
 };
-use staking_contract::msg::ConfigResponse;
 
 use crate::{
-    helpers::{adjust, deposit_one_coin},
-    query::{max_payout, payout_for, percent_vested_for},
-    state::{bond_price, BOND_INFO, CONFIG, LAST_DECAY, TERMS, TOTAL_DEBT},
+    contract::INSTANTIATE_NOTE_NFT_REPLY,
+    helpers::{adjust, deposit_one_coin, tune},
+    msg::{DepositCallbackMsg, DepositResponse, ReceiveMsg},
+    query::{
+        discount, load_note, market_price, max_payout, note_holder, ohm_denom, payout_for,
+        percent_vested, query_tokenized_note, quote_value,
+    },
+    state::{
+        assert_not_paused, bond_price, load_market, record_history, Auction, AutoRedeem,
+        HistoryEvent, Market, Note, Operation, PricingModel, VestingMode, AUCTIONS, AUTO_REDEEM,
+        CONFIG, MARKETS, NEXT_NOTE_ID, NOTES, PURCHASED, REFERRAL_REWARDS, REFERRERS, TERMS,
+        TOKENIZED_NOTES, TUNINGS,
+    },
     ContractError,
 };
 
@@ -15,134 +32,717 @@ pub const INITIAL_FRAGMENTS_SUPPLY: u128 = 5_000_000;
 pub const TOTAL_GONS: u128 = u128::MAX - (u128::MAX % INITIAL_FRAGMENTS_SUPPLY);
 pub const MAX_SUPPLY: u128 = u64::MAX as u128;
 
-pub fn current_debt(deps: Deps, env: Env) -> Result<Uint128, ContractError> {
-    let total_debt = TOTAL_DEBT.load(deps.storage)?;
-    Ok(total_debt - debt_decay(deps, env)?)
+pub fn current_debt(deps: Deps, env: Env, market_id: u64) -> Result<Uint128, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    Ok(market.total_debt - debt_decay(deps, env, market_id)?)
 }
 
-pub fn debt_decay(deps: Deps, env: Env) -> Result<Uint128, StdError> {
-    let terms = TERMS.load(deps.storage)?;
-    let last_decay = LAST_DECAY.load(deps.storage)?;
-    let time_since_last_decay = env.block.time.seconds() - last_decay.seconds();
-    let total_debt = TOTAL_DEBT.load(deps.storage)?;
+pub fn debt_decay(deps: Deps, env: Env, market_id: u64) -> Result<Uint128, ContractError> {
+    let terms = TERMS.load(deps.storage, market_id)?;
+    let market = load_market(deps.storage, market_id)?;
+    let time_since_last_decay = env.block.time.seconds() - market.last_decay.seconds();
+    // Fixed expiry markets decay their debt until the expiry
+    let decay_length = terms.vesting_time_left(market.last_decay);
+    if decay_length == 0 {
+        return Ok(market.total_debt);
+    }
 
     let mut decay =
-        total_debt * Uint128::from(time_since_last_decay) / Uint128::from(terms.vesting_term);
-    if decay > total_debt {
-        decay = total_debt;
+        market.total_debt * Uint128::from(time_since_last_decay) / Uint128::from(decay_length);
+    if decay > market.total_debt {
+        decay = market.total_debt;
     }
     Ok(decay)
 }
 
-pub fn decay_debt(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<(), ContractError> {
-    let debt_decay = debt_decay(deps.as_ref(), env.clone())?;
-    TOTAL_DEBT.update(deps.storage, |d| Ok::<_, ContractError>(d - debt_decay))?;
-    LAST_DECAY.save(deps.storage, &env.block.time)?;
+pub fn decay_debt(deps: DepsMut, env: Env, market_id: u64) -> Result<Market, ContractError> {
+    let debt_decay = debt_decay(deps.as_ref(), env.clone(), market_id)?;
+    let mut market = load_market(deps.storage, market_id)?;
+    market.total_debt -= debt_decay;
+    market.last_decay = env.block.time;
+    MARKETS.save(deps.storage, market_id, &market)?;
 
-    Ok(())
+    Ok(market)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn deposit(
-    mut deps: DepsMut,
+    deps: DepsMut,
     env: Env,
     info: MessageInfo,
+    market_id: u64,
+    max_price: Decimal256,
+    depositor: String,
+    referrer: Option<String>,
+    proof: Option<Vec<String>>,
+    callback: Option<Binary>,
+) -> Result<Response, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    let sender = info.sender.clone();
+    let deposited_amount = match market.quote_token {
+        AssetInfo::Native(denom) => deposit_one_coin(info, denom)?,
+        quote_token => {
+            return Err(ContractError::WrongQuoteToken {
+                market_id,
+                quote_token,
+            })
+        }
+    };
+
+    _deposit(
+        deps,
+        env,
+        market_id,
+        max_price,
+        depositor,
+        referrer,
+        proof,
+        deposited_amount,
+        callback.map(|callback| (sender, callback)),
+    )
+}
+
+/// Handles the hooks of the cw20 quote tokens, the sender is the token contract
+pub fn receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    match from_json(&msg.msg)? {
+        ReceiveMsg::Deposit {
+            market_id,
+            max_price,
+            depositor,
+            referrer,
+            proof,
+            callback,
+        } => {
+            let market = load_market(deps.storage, market_id)?;
+            ensure!(
+                market.quote_token == AssetInfo::Cw20(info.sender),
+                ContractError::WrongQuoteToken {
+                    market_id,
+                    quote_token: market.quote_token,
+                }
+            );
+
+            let callback = callback
+                .map(|callback| {
+                    deps.api
+                        .addr_validate(&msg.sender)
+                        .map(|sender| (sender, callback))
+                })
+                .transpose()?;
+            _deposit(
+                deps, env, market_id, max_price, depositor, referrer, proof, msg.amount, callback,
+            )
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn _deposit(
+    mut deps: DepsMut,
+    env: Env,
+    market_id: u64,
     max_price: Decimal256,
     depositor: String,
+    referrer: Option<String>,
+    proof: Option<Vec<String>>,
+    deposited_amount: Uint128,
+    callback: Option<(Addr, Binary)>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Deposit)?;
     let config = CONFIG.load(deps.storage)?;
+    let market = load_market(deps.storage, market_id)?;
+    ensure!(market.is_open(), ContractError::MarketClosed { market_id });
 
-    let deposited_amount = deposit_one_coin(info.clone(), config.principle.clone())?;
+    let depositor_addr = deps.api.addr_validate(&depositor)?;
+    if let Some(allow_list) = &market.allow_list {
+        ensure!(
+            allow_list.is_allowed(&depositor_addr, &proof.unwrap_or_default())?,
+            ContractError::NotAllowed { market_id }
+        );
+        let purchased = PURCHASED
+            .may_load(deps.storage, (market_id, &depositor_addr))?
+            .unwrap_or_default()
+            + deposited_amount;
+        if let Some(cap) = allow_list.cap {
+            ensure!(
+                purchased <= cap,
+                ContractError::AllowListCapExceeded { market_id }
+            );
+        }
+        PURCHASED.save(deps.storage, (market_id, &depositor_addr), &purchased)?;
+    }
 
-    decay_debt(deps.branch(), env.clone(), info)?;
-    let total_debt = TOTAL_DEBT.load(deps.storage)?;
-    let terms = TERMS.load(deps.storage)?;
+    let mut market = decay_debt(deps.branch(), env.clone(), market_id)?;
+    // The market is tuned before the deposit is priced
+    tune(deps.branch(), &env, market_id)?;
+    let terms = TERMS.load(deps.storage, market_id)?;
+    if let VestingMode::FixedExpiry(expiry) = terms.vesting {
+        ensure!(
+            env.block.time < expiry,
+            ContractError::MarketExpired { market_id }
+        );
+    }
+    if let Some(tuning) = TUNINGS.may_load(deps.storage, market_id)? {
+        ensure!(
+            env.block.time < tuning.config.conclusion,
+            ContractError::MarketConcluded { market_id }
+        );
+    }
 
     ensure!(
-        total_debt <= terms.max_debt,
+        market.total_debt <= terms.max_debt,
         StdError::generic_err("Max capacity reached")
     );
 
-    let native_price = bond_price(deps.branch(), env.clone())?;
+    let native_price = bond_price(deps.branch(), env.clone(), market_id)?;
 
-    ensure!(
-        max_price >= native_price,
-        StdError::generic_err("Slippage limit: more than max price")
-    );
-    let payout = payout_for(deps.as_ref(), env.clone(), deposited_amount)?;
+    if market.oracle.as_ref().is_some_and(|o| o.require_discount) {
+        ensure!(
+            !discount(native_price, market_price(deps.as_ref(), market_id)?).negative,
+            ContractError::NegativeDiscount {}
+        );
+    }
+
+    let value = quote_value(deps.as_ref(), market_id, deposited_amount)?;
+    let payout = payout_for(deps.as_ref(), env.clone(), market_id, value)?;
 
     ensure!(
         payout.u128() >= 1_000,
         StdError::generic_err("Bond too small")
     ); // must be > 0.001 OHM ( underflow protection )
+
+    // Locked payouts lose the deposit fee of the lock contract, the depositor is quoted
+    // the price of what ends up locked
+    let lock_contract = if terms.lock_payout {
+        Some(
+            config
+                .lock_contract
+                .as_ref()
+                .ok_or(ContractError::NoLockContract {})?,
+        )
+    } else {
+        None
+    };
+    let received = match lock_contract {
+        Some(lock_contract) => locked_amount(
+            deps.as_ref(),
+            lock_contract,
+            ohm_denom(deps.as_ref())?,
+            payout,
+        )?,
+        None => payout,
+    };
+    ensure!(!received.is_zero(), StdError::generic_err("Bond too small"));
+    let price = native_price * Decimal256::from_ratio(payout, received);
     ensure!(
-        payout <= max_payout(deps.as_ref())?,
+        max_price >= price,
+        StdError::generic_err("Slippage limit: more than max price")
+    );
+    let max_payout = max_payout(deps.as_ref(), market_id)?;
+    ensure!(
+        payout <= max_payout,
         StdError::generic_err("Bond too large")
     ); // size protection because there is no slippage
 
-    let treasury_msg = CosmosMsg::Bank(BankMsg::Send {
-        to_address: config.treasury.to_string(),
-        amount: vec![Coin {
-            amount: deposited_amount,
-            denom: config.principle.clone(),
-        }],
-    });
+    let used_capacity = if market.capacity_in_quote {
+        deposited_amount
+    } else {
+        payout
+    };
+    ensure!(
+        used_capacity <= market.capacity,
+        ContractError::MarketCapacityExceeded { market_id }
+    );
 
-    let mint_msg = CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
-        contract_addr: config.staking.to_string(),
-        msg: to_json_binary(&staking_contract::msg::ExecuteMsg::Mint {
-            to: env.contract.address.to_string(),
-            amount: payout,
-        })?,
-        funds: vec![],
-    });
+    let treasury_msg =
+        Asset::new(market.quote_token.clone(), deposited_amount).transfer_msg(&config.treasury)?;
 
-    TOTAL_DEBT.update(deps.storage, |d| Ok::<_, ContractError>(d + payout))?;
+    // Referral rewards are minted with the payout and held until claimed
+    let referral_reward = match referrer {
+        Some(referrer) => {
+            let referrer = deps.api.addr_validate(&referrer)?;
+            let rate =
+                REFERRERS
+                    .may_load(deps.storage, &referrer)?
+                    .ok_or(ContractError::NotReferrer {
+                        referrer: referrer.to_string(),
+                    })?;
+            let reward = Uint128::try_from(Uint256::from(payout) * rate)?;
+            REFERRAL_REWARDS.update(deps.storage, &referrer, |rewards| {
+                Ok::<_, ContractError>(rewards.unwrap_or_default() + reward)
+            })?;
+            reward
+        }
+        None => Uint128::zero(),
+    };
+    let mint_msg = mint_msg(
+        &config.staking,
+        &env.contract.address,
+        payout + referral_reward,
+    )?;
+    // The DAO share is minted on top of the payout and counts as debt of the market
+    let dao_fee = Uint128::try_from(Uint256::from(payout) * config.dao_fee)?;
+    let dao_msg = match &config.dao_address {
+        Some(dao_address) if !dao_fee.is_zero() => {
+            Some(mint_msg(&config.staking, dao_address, dao_fee)?)
+        }
+        _ => None,
+    };
 
-    let depositor_addr = deps.api.addr_validate(&depositor)?;
-    BOND_INFO.update(deps.storage, &depositor_addr, |b| {
-        let mut bond = b.unwrap_or_default();
+    market.capacity -= used_capacity;
+    market.total_debt += payout + dao_fee + referral_reward;
+    market.sold += payout;
+    market.purchased += deposited_amount;
+    MARKETS.save(deps.storage, market_id, &market)?;
+    if let PricingModel::SequentialDutchAuction(params) = &terms.pricing {
+        AUCTIONS.save(
+            deps.storage,
+            market_id,
+            &Auction {
+                price: params.bumped_price(native_price, payout, max_payout),
+                last_update: env.block.time,
+            },
+        )?;
+    }
+
+    let vesting_time_left = terms.vesting_time_left(env.block.time);
+    let (note_id, lock_msg) = if let Some(lock_contract) = lock_contract {
+        let msg = lock_msg(
+            lock_contract,
+            &depositor_addr,
+            ohm_denom(deps.as_ref())?,
+            payout,
+            received,
+            env.block.time,
+            vesting_time_left,
+        )?;
+        (None, Some(msg))
+    } else {
+        let note_id = NEXT_NOTE_ID.may_load(deps.storage)?.unwrap_or_default();
+        NEXT_NOTE_ID.save(deps.storage, &(note_id + 1))?;
+        NOTES.save(
+            deps.storage,
+            (&depositor_addr, note_id),
+            &Note {
+                market_id,
+                payout,
+                vesting_time_left,
+                last_time: env.block.time,
+            },
+        )?;
+        (Some(note_id), None)
+    };
 
-        bond.payout += payout;
-        bond.vesting_time_left = terms.vesting_term;
-        bond.last_time = env.clone().block.time;
+    record_history(
+        deps.storage,
+        &depositor_addr,
+        HistoryEvent::Deposit {
+            market_id,
+            note_id,
+            amount: deposited_amount,
+            price,
+            payout: received,
+        },
+        env.block.time,
+    )?;
 
-        Ok::<_, ContractError>(bond)
-    })?;
+    let deposit_response = DepositResponse {
+        payout: received,
+        vesting_end: env.block.time.plus_seconds(vesting_time_left),
+        note_id,
+        price,
+    };
+    let callback_msg = callback
+        .map(|(sender, msg)| {
+            DepositCallbackMsg {
+                deposit: deposit_response.clone(),
+                msg,
+            }
+            .into_cosmos_msg(sender)
+        })
+        .transpose()?;
 
-    adjust(deps, env)?;
+    adjust(deps, env.clone(), market_id)?;
     Ok(Response::new()
+        .set_data(to_json_binary(&deposit_response)?)
         .add_message(treasury_msg)
-        .add_message(mint_msg))
+        .add_message(mint_msg)
+        .add_messages(dao_msg)
+        .add_messages(lock_msg)
+        .add_messages(callback_msg)
+        .add_attribute("action", "deposit")
+        .add_attribute("market_id", market_id.to_string())
+        .add_attribute("depositor", depositor_addr)
+        .add_attribute("amount", deposited_amount)
+        .add_attribute("price", price.to_string())
+        .add_attribute("payout", received)
+        .add_attributes(note_id.map(|id| ("note_id", id.to_string())))
+        .add_attribute("dao_fee", dao_fee)
+        .add_attribute("referral_reward", referral_reward))
 }
 
+/// Sends the referral rewards accrued by the sender
+pub fn claim_referral_rewards(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let rewards = REFERRAL_REWARDS
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if rewards.is_zero() {
+        Err(StdError::generic_err("No referral rewards to claim"))?;
+    }
+    REFERRAL_REWARDS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: vec![Coin {
+                denom: ohm_denom(deps.as_ref())?,
+                amount: rewards,
+            }],
+        })
+        .add_attribute("action", "claim_referral_rewards")
+        .add_attribute("referrer", info.sender)
+        .add_attribute("amount", rewards))
+}
+
+/// Mints OHM through the staking contract, the depository has to be one of its minters
+fn mint_msg(staking: &Addr, to: &Addr, amount: Uint128) -> StdResult<CosmosMsg> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: staking.to_string(),
+        msg: to_json_binary(&staking_contract::msg::ExecuteMsg::Mint {
+            to: to.to_string(),
+            amount,
+        })?,
+        funds: vec![],
+    }))
+}
+
+/// Amount left of the payout once the deposit fee of the lock contract is taken
+fn locked_amount(
+    deps: Deps,
+    contract: &Addr,
+    ohm_denom: String,
+    payout: Uint128,
+) -> Result<Uint128, ContractError> {
+    let fee: Fee = deps.querier.query_wasm_smart(
+        contract,
+        &lock_contract::msg::QueryMsg::DepositFee {
+            asset: AssetInfoUnchecked::native(&ohm_denom),
+        },
+    )?;
+    Ok(fee
+        .apply(Asset::native(ohm_denom, payout))
+        .map_err(|err| StdError::generic_err(err.to_string()))?
+        .amount)
+}
+
+/// Locks the payout for the depositor, the `locked` part vests linearly until the end of
+/// the term and the rest pays the deposit fee of the lock contract
+fn lock_msg(
+    contract: &Addr,
+    depositor: &Addr,
+    ohm_denom: String,
+    payout: Uint128,
+    locked: Uint128,
+    start: Timestamp,
+    vesting_time_left: u64,
+) -> Result<CosmosMsg, ContractError> {
+    Ok(CosmosMsg::Wasm(WasmMsg::Execute {
+        contract_addr: contract.to_string(),
+        msg: to_json_binary(&lock_contract::msg::ExecuteMsg::Lock {
+            to: depositor.to_string(),
+            asset: AssetUnchecked::native(ohm_denom.clone(), payout),
+            lock: DepositLock::Linear(LinearLock {
+                start,
+                per_second_vesting: Decimal256::from_ratio(locked, vesting_time_left.max(1)),
+            }),
+        })?,
+        funds: vec![Coin {
+            denom: ohm_denom,
+            amount: payout,
+        }],
+    }))
+}
+
+/// Redeems the vested part of the recipient's notes, all of them when `note_ids` is `None`.
+/// Tokenized notes have to be listed, they are paid to the recipient only if it holds the token
 pub fn redeem(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     _info: MessageInfo,
     recipient: String,
     stake: bool,
+    note_ids: Option<Vec<u64>>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Redeem)?;
     let recipient_addr = deps.api.addr_validate(&recipient)?;
-    let mut bond = BOND_INFO.load(deps.storage, &recipient_addr)?;
-    let percent_vested = percent_vested_for(deps.as_ref(), env.clone(), &recipient_addr)?;
-    if percent_vested >= Decimal256::one() {
-        BOND_INFO.remove(deps.storage, &recipient_addr);
-        stake_or_send(deps.as_ref(), recipient_addr, stake, bond.payout)
-    } else {
-        let payout = Uint256::from(bond.payout) * percent_vested;
+    let (total_payout, burn_msgs) = vest_notes(deps.branch(), &env, &recipient_addr, note_ids)?;
 
+    if total_payout.is_zero() {
+        Err(StdError::generic_err("Nothing to redeem here !"))?;
+    }
+
+    record_history(
+        deps.storage,
+        &recipient_addr,
+        HistoryEvent::Redeem {
+            payout: total_payout,
+            stake,
+        },
+        env.block.time,
+    )?;
+
+    Ok(
+        stake_or_send(deps.as_ref(), recipient_addr.clone(), stake, total_payout)?
+            .add_messages(burn_msgs)
+            .add_attribute("action", "redeem")
+            .add_attribute("recipient", recipient_addr)
+            .add_attribute("payout", total_payout)
+            .add_attribute("stake", stake.to_string()),
+    )
+}
+
+/// Redeems the notes of the recipients that opted in, as they chose in `SetAutoRedeem`.
+/// The caller is paid the tips of the recipients and stakes them if `stake` is set
+pub fn redeem_for(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipients: Vec<String>,
+    stake: bool,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Redeem)?;
+    let mut recipients = recipients
+        .iter()
+        .map(|recipient| deps.api.addr_validate(recipient))
+        .collect::<StdResult<Vec<_>>>()?;
+    recipients.sort_unstable();
+    recipients.dedup();
+
+    let mut msgs = vec![];
+    let mut total_tip = Uint128::zero();
+    for recipient in recipients {
+        let Some(preference) = AUTO_REDEEM.may_load(deps.storage, &recipient)? else {
+            continue;
+        };
+        // Tokenized notes are never listed here, so there is nothing to burn
+        let (payout, _) = vest_notes(deps.branch(), &env, &recipient, None)?;
         if payout.is_zero() {
-            Err(StdError::generic_err("Nothing to redeem here !"))?;
+            continue;
+        }
+        let tip = Uint128::try_from(Uint256::from(payout) * preference.tip)?;
+        record_history(
+            deps.storage,
+            &recipient,
+            HistoryEvent::Redeem {
+                payout: payout - tip,
+                stake: preference.stake,
+            },
+            env.block.time,
+        )?;
+        msgs.push(payout_msg(
+            deps.as_ref(),
+            &recipient,
+            preference.stake,
+            payout - tip,
+        )?);
+        total_tip += tip;
+    }
+
+    if msgs.is_empty() {
+        Err(StdError::generic_err("Nothing to redeem here !"))?;
+    }
+    let redeemed = msgs.len();
+    if !total_tip.is_zero() {
+        msgs.push(payout_msg(deps.as_ref(), &info.sender, stake, total_tip)?);
+    }
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "redeem_for")
+        .add_attribute("keeper", info.sender)
+        .add_attribute("redeemed", redeemed.to_string())
+        .add_attribute("tip", total_tip))
+}
+
+pub fn set_auto_redeem(
+    deps: DepsMut,
+    info: MessageInfo,
+    stake: bool,
+    tip: Decimal256,
+) -> Result<Response, ContractError> {
+    ensure!(tip < Decimal256::one(), ContractError::InvalidTip {});
+    AUTO_REDEEM.save(deps.storage, &info.sender, &AutoRedeem { stake, tip })?;
+
+    Ok(Response::new()
+        .add_attribute("action", "set_auto_redeem")
+        .add_attribute("address", info.sender)
+        .add_attribute("stake", stake.to_string())
+        .add_attribute("tip", tip.to_string()))
+}
+
+pub fn disable_auto_redeem(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    AUTO_REDEEM.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_attribute("action", "disable_auto_redeem")
+        .add_attribute("address", info.sender))
+}
+
+/// Vests the recipient's notes and returns their total vested payout,
+/// along with the burns of the tokenized notes that were paid out in full
+fn vest_notes(
+    deps: DepsMut,
+    env: &Env,
+    recipient_addr: &Addr,
+    note_ids: Option<Vec<u64>>,
+) -> Result<(Uint128, Vec<WasmMsg>), ContractError> {
+    let mut note_ids = match note_ids {
+        Some(note_ids) => note_ids,
+        None => NOTES
+            .prefix(&recipient_addr)
+            .keys(deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?,
+    };
+    note_ids.sort_unstable();
+    note_ids.dedup();
+
+    let mut total_payout = Uint128::zero();
+    let mut burn_msgs = vec![];
+    for note_id in note_ids {
+        if let Some(note) = NOTES.may_load(deps.storage, (recipient_addr, note_id))? {
+            let (payout, note) = vest(note, env)?;
+            match note {
+                Some(note) => NOTES.save(deps.storage, (recipient_addr, note_id), &note)?,
+                None => NOTES.remove(deps.storage, (recipient_addr, note_id)),
+            }
+            total_payout += payout;
+        } else {
+            let note = query_tokenized_note(deps.as_ref(), note_id)?;
+            ensure!(
+                note_holder(deps.as_ref(), note_id)? == recipient_addr,
+                ContractError::Unauthorized {}
+            );
+            let (payout, note) = vest(note, env)?;
+            match note {
+                Some(note) => TOKENIZED_NOTES.save(deps.storage, note_id, &note)?,
+                None => {
+                    TOKENIZED_NOTES.remove(deps.storage, note_id);
+                    burn_msgs.push(burn_note_msg(deps.as_ref(), note_id)?);
+                }
+            }
+            total_payout += payout;
         }
+    }
 
-        bond.payout -= Uint128::try_from(payout)?;
-        bond.vesting_time_left -= env.block.time.seconds() - bond.last_time.seconds();
-        bond.last_time = env.block.time;
+    Ok((total_payout, burn_msgs))
+}
 
-        BOND_INFO.save(deps.storage, &recipient_addr, &bond)?;
+/// Burns the NFT of a tokenized note that was paid out in full
+fn burn_note_msg(deps: Deps, note_id: u64) -> Result<WasmMsg, ContractError> {
+    let note_nft = CONFIG
+        .load(deps.storage)?
+        .note_nft
+        .ok_or(ContractError::NoNoteNft {})?;
+    Ok(WasmMsg::Execute {
+        contract_addr: note_nft.to_string(),
+        msg: to_json_binary(&bond_note::msg::ExecuteMsg::Burn {
+            token_id: note_id.to_string(),
+        })?,
+        funds: vec![],
+    })
+}
 
-        stake_or_send(deps.as_ref(), recipient_addr, stake, payout.try_into()?)
+/// Splits the vested payout out of the note, the note is `None` once it has fully vested
+pub fn vest(mut note: Note, env: &Env) -> Result<(Uint128, Option<Note>), ContractError> {
+    let percent_vested = percent_vested(&note, env);
+    if percent_vested >= Decimal256::one() {
+        return Ok((note.payout, None));
+    }
+
+    let payout = Uint128::try_from(Uint256::from(note.payout) * percent_vested)?;
+    if !payout.is_zero() {
+        note.payout -= payout;
+        note.vesting_time_left -= env.block.time.seconds() - note.last_time.seconds();
+        note.last_time = env.block.time;
     }
+    Ok((payout, Some(note)))
+}
+
+pub fn instantiate_note_nft(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    code_id: u64,
+    name: String,
+    symbol: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+
+    if config.note_nft.is_some() {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    let instantiate_msg = SubMsg::reply_on_success(
+        CosmosMsg::Wasm(WasmMsg::Instantiate {
+            admin: Some(env.contract.address.to_string()),
+            code_id,
+            msg: to_json_binary(&bond_note::msg::InstantiateMsg {
+                name,
+                symbol,
+                minter: env.contract.address.to_string(),
+                withdraw_address: None,
+            })?,
+            funds: vec![],
+            label: "Bond notes".to_string(),
+        }),
+        INSTANTIATE_NOTE_NFT_REPLY,
+    );
+
+    Ok(Response::new().add_submessage(instantiate_msg))
+}
+
+/// Moves the note of the sender to the NFT contract, the token id is the note id
+pub fn tokenize_note(
+    deps: DepsMut,
+    info: MessageInfo,
+    note_id: u64,
+) -> Result<Response, ContractError> {
+    let note_nft = CONFIG
+        .load(deps.storage)?
+        .note_nft
+        .ok_or(ContractError::NoNoteNft {})?;
+    let note = load_note(deps.as_ref(), &info.sender, note_id)?;
+    let market = load_market(deps.storage, note.market_id)?;
+
+    NOTES.remove(deps.storage, (&info.sender, note_id));
+    TOKENIZED_NOTES.save(deps.storage, note_id, &note)?;
+
+    let mint_msg = WasmMsg::Execute {
+        contract_addr: note_nft.to_string(),
+        msg: to_json_binary(&bond_note::msg::ExecuteMsg::Mint {
+            token_id: note_id.to_string(),
+            owner: info.sender.to_string(),
+            token_uri: None,
+            extension: NoteMetadata {
+                market_id: note.market_id,
+                payout: note.payout,
+                vesting_end: note.last_time.plus_seconds(note.vesting_time_left),
+                quote_token: market.quote_id(),
+            },
+        })?,
+        funds: vec![],
+    };
+
+    Ok(Response::new()
+        .add_message(mint_msg)
+        .add_attribute("action", "tokenize_note")
+        .add_attribute("note_id", note_id.to_string()))
 }
 
 pub fn stake_or_send(
@@ -151,16 +751,20 @@ pub fn stake_or_send(
     stake: bool,
     payout: Uint128,
 ) -> Result<Response, ContractError> {
+    Ok(Response::new().add_message(payout_msg(deps, &recipient, stake, payout)?))
+}
+
+/// Sends `payout` OHM to the recipient, or stakes it for them
+fn payout_msg(
+    deps: Deps,
+    recipient: &Addr,
+    stake: bool,
+    payout: Uint128,
+) -> Result<CosmosMsg, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let staking_config: ConfigResponse = deps.querier.query(&cosmwasm_std::QueryRequest::Wasm(
-        cosmwasm_std::WasmQuery::Smart {
-            contract_addr: config.staking.to_string(),
-            msg: to_json_binary(&staking_contract::msg::QueryMsg::Config {})?,
-        },
-    ))?;
     let payout_coins = vec![Coin {
         amount: payout,
-        denom: staking_config.ohm_denom,
+        denom: ohm_denom(deps)?,
     }];
 
     let msgs = if !stake {
@@ -177,5 +781,5 @@ pub fn stake_or_send(
             funds: payout_coins,
         })
     };
-    Ok(Response::new().add_message(msgs))
+    Ok(msgs)
 }
@@ -1,7 +1,14 @@
-use cosmwasm_std::{Decimal256, DepsMut, Env, MessageInfo, Uint128};
+use cosmwasm_std::{
+    Addr, BankMsg, CosmosMsg, Decimal256, Deps, DepsMut, Env, MessageInfo, StdResult, Storage,
+    Timestamp, Uint128, Uint256,
+};
 
 use crate::{
-    state::{ADJUSTMENT, TERMS},
+    query::{payout_for, quote_value, total_base_supply},
+    state::{
+        load_market, query_bond_price, Adjustment, AdjustmentStep, ADJUSTMENTS,
+        ADJUSTMENT_SCHEDULES, TERMS, TUNINGS,
+    },
     ContractError,
 };
 
@@ -18,9 +25,15 @@ pub fn deposit_one_coin(info: MessageInfo, denom: String) -> Result<Uint128, Con
     Ok(deposited_coin.amount)
 }
 
-pub fn adjust(deps: DepsMut, env: Env) -> Result<(), ContractError> {
-    let mut adjustment = ADJUSTMENT.load(deps.storage)?;
-    let mut terms = TERMS.load(deps.storage)?;
+pub fn adjust(deps: DepsMut, env: Env, market_id: u64) -> Result<(), ContractError> {
+    let mut adjustment = ADJUSTMENTS.load(deps.storage, market_id)?;
+    if adjustment.rate.is_zero() {
+        if let Some(step) = next_adjustment_step(deps.storage, market_id, env.block.time)? {
+            adjustment = step.into();
+            ADJUSTMENTS.save(deps.storage, market_id, &adjustment)?;
+        }
+    }
+    let mut terms = TERMS.load(deps.storage, market_id)?;
     let time_can_adjust = adjustment.last_time.plus_seconds(adjustment.buffer);
     if !adjustment.rate.is_zero() && env.block.time > time_can_adjust {
         if adjustment.add {
@@ -37,9 +50,111 @@ pub fn adjust(deps: DepsMut, env: Env) -> Result<(), ContractError> {
                 adjustment.rate = Decimal256::zero();
             }
         }
-        ADJUSTMENT.save(deps.storage, &adjustment)?;
-        TERMS.save(deps.storage, &terms)?;
+        ADJUSTMENTS.save(deps.storage, market_id, &adjustment)?;
+        TERMS.save(deps.storage, market_id, &terms)?;
+    }
+
+    Ok(())
+}
+
+/// Takes the first step out of the schedule of the market if it has started
+fn next_adjustment_step(
+    storage: &mut dyn Storage,
+    market_id: u64,
+    now: Timestamp,
+) -> Result<Option<AdjustmentStep>, ContractError> {
+    let mut steps = ADJUSTMENT_SCHEDULES
+        .may_load(storage, market_id)?
+        .unwrap_or_default();
+    match steps.first() {
+        Some(step) if step.start <= now => {}
+        _ => return Ok(None),
+    }
+    let step = steps.remove(0);
+    ADJUSTMENT_SCHEDULES.save(storage, market_id, &steps)?;
+    Ok(Some(step))
+}
+
+/// Sets the control variable and the max payout of a tuned market so that its capacity sells out
+/// evenly until the conclusion, as Olympus V2 `_tune`. Decreases of the control variable are
+/// spread over the tune interval through the adjustment of the market.
+/// While an adjustment is in progress, the control variable is left to it and only the max payout
+/// is tuned
+pub fn tune(deps: DepsMut, env: &Env, market_id: u64) -> Result<(), ContractError> {
+    let Some(mut tuning) = TUNINGS.may_load(deps.storage, market_id)? else {
+        return Ok(());
+    };
+    let now = env.block.time;
+    if now < tuning.next_tune() || now >= tuning.config.conclusion {
+        return Ok(());
+    }
+
+    let market = load_market(deps.storage, market_id)?;
+    let mut terms = TERMS.load(deps.storage, market_id)?;
+    let time_remaining = tuning.config.conclusion.seconds() - now.seconds();
+    let price = query_bond_price(deps.as_ref(), env.clone(), market_id)?;
+    let supply = total_base_supply(deps.as_ref())?;
+
+    // Capacity left in OHM
+    let capacity = if market.capacity_in_quote {
+        let value = quote_value(deps.as_ref(), market_id, market.capacity)?;
+        payout_for(deps.as_ref(), env.clone(), market_id, value)?
+    } else {
+        market.capacity
+    };
+
+    // Max size deposits every deposit interval sell the capacity on time
+    terms.max_payout = Decimal256::checked_from_ratio(
+        Uint256::from(capacity) * Uint256::from(tuning.config.deposit_interval)
+            / Uint256::from(time_remaining),
+        supply,
+    )?;
+
+    // Debt of the market if it sells at that pace, it decays over the vesting length
+    let target_debt = Uint256::from(capacity) * Uint256::from(terms.vesting_time_left(now))
+        / Uint256::from(time_remaining);
+    let adjusting = ADJUSTMENTS
+        .may_load(deps.storage, market_id)?
+        .is_some_and(|adjustment| !adjustment.rate.is_zero());
+    if !target_debt.is_zero() && !adjusting {
+        let control_variable = price * Decimal256::checked_from_ratio(supply, target_debt)?;
+        if control_variable >= terms.control_variable {
+            terms.control_variable = control_variable;
+        } else {
+            let change = terms.control_variable - control_variable;
+            ADJUSTMENTS.save(
+                deps.storage,
+                market_id,
+                &Adjustment {
+                    add: false,
+                    rate: change
+                        * Decimal256::from_ratio(
+                            tuning.config.deposit_interval,
+                            tuning.config.tune_interval,
+                        ),
+                    target: control_variable,
+                    buffer: 0,
+                    last_time: now,
+                },
+            )?;
+        }
     }
 
+    TERMS.save(deps.storage, market_id, &terms)?;
+    tuning.last_tune = now;
+    TUNINGS.save(deps.storage, market_id, &tuning)?;
+
     Ok(())
 }
+
+/// Sends every native balance of the contract to `recipient`
+pub fn sweep_msgs(deps: Deps, env: &Env, recipient: &Addr) -> StdResult<Vec<CosmosMsg>> {
+    let balances = deps.querier.query_all_balances(&env.contract.address)?;
+    if balances.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: balances,
+    })])
+}
@@ -1,8 +1,8 @@
-use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 use cosmwasm_std::Empty;
 use cw_orch::{interface, prelude::*};
 
-#[interface(InstantiateMsg, ExecuteMsg, QueryMsg, Empty)]
+#[interface(InstantiateMsg, ExecuteMsg, QueryMsg, MigrateMsg)]
 pub struct Bond;
 
 impl<Chain: CwEnv> Uploadable for Bond<Chain> {
@@ -14,10 +14,14 @@ impl<Chain: CwEnv> Uploadable for Bond<Chain> {
     }
     /// Returns a CosmWasm contract wrapper
     fn wrapper(&self) -> Box<dyn MockContract<Empty>> {
-        Box::new(ContractWrapper::new_with_empty(
-            crate::contract::execute,
-            crate::contract::instantiate,
-            crate::contract::query,
-        ))
+        Box::new(
+            ContractWrapper::new_with_empty(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_migrate(crate::contract::migrate)
+            .with_sudo(crate::contract::sudo),
+        )
     }
 }
@@ -5,6 +5,8 @@ pub mod msg;
 pub mod query;
 pub mod state;
 pub use crate::error::ContractError;
+pub use crate::msg::{DepositCallbackExecuteMsg, DepositCallbackMsg};
 pub mod helpers;
 #[cfg(feature = "interface")]
 pub mod interface;
+pub mod migrate;
@@ -0,0 +1,361 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal256, DepsMut, Order, StdResult, Timestamp, Uint128};
+use cw_asset::{AssetInfo, AssetInfoUnchecked};
+use cw_storage_plus::{Item, Map};
+use semver::Version;
+use staking_contract::state::OHM_DECIMALS;
+
+use crate::{
+    query::quote_decimals,
+    state::{
+        Adjustment, Config, Market, Note, PricingModel, Terms, VestingMode, ADJUSTMENTS,
+        ADJUSTMENT_SCHEDULES, AUCTIONS, CONFIG, DECIMALS, MARKETS, NEXT_MARKET_ID, NEXT_NOTE_ID,
+        NOTES, TERMS,
+    },
+    ContractError,
+};
+
+/// Config layout used before cw2 versioning, the admin was stored in the config
+#[cw_serde]
+struct LegacyConfig {
+    pub principle: String,
+    pub admin: Addr,
+    pub staking: Addr,
+    pub treasury: Addr,
+}
+
+/// Terms layout used before cw2 versioning
+#[cw_serde]
+struct LegacyTerms {
+    pub control_variable: Decimal256,
+    pub minimum_price: Decimal256,
+    pub max_payout: Decimal256,
+    pub max_debt: Uint128,
+    pub vesting_term: u64,
+}
+
+impl From<LegacyTerms> for Terms {
+    fn from(terms: LegacyTerms) -> Self {
+        Terms {
+            control_variable: terms.control_variable,
+            minimum_price: terms.minimum_price,
+            max_payout: terms.max_payout,
+            max_debt: terms.max_debt,
+            vesting: VestingMode::FixedTerm(terms.vesting_term),
+            pricing: PricingModel::DebtRatio,
+            lock_payout: false,
+        }
+    }
+}
+
+/// Bond merging all the deposits of an address, it becomes a single note
+#[cw_serde]
+struct LegacyBond {
+    pub payout: Uint128,
+    pub vesting_time_left: u64,
+    pub last_time: Timestamp,
+}
+
+const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+const LEGACY_TERMS: Item<LegacyTerms> = Item::new("terms");
+const LEGACY_TOTAL_DEBT: Item<Uint128> = Item::new("total_debt");
+const LEGACY_LAST_DECAY: Item<Timestamp> = Item::new("last_decay");
+const LEGACY_ADJUSTMENT: Item<Adjustment> = Item::new("adjustment");
+const LEGACY_BOND_INFO: Map<&Addr, LegacyBond> = Map::new("bond_info");
+
+/// Transforms the storage written by `from` into the current layout.
+/// `from` is `None` for contracts instantiated before cw2 versioning.
+/// `decimals` are saved first, the quote decimals of the legacy market can't be set before
+pub fn migrate_storage(
+    mut deps: DepsMut,
+    from: Option<&Version>,
+    decimals: Vec<(AssetInfoUnchecked, u8)>,
+) -> Result<(), ContractError> {
+    for (asset, decimals) in decimals {
+        if u32::from(decimals) > Decimal256::DECIMAL_PLACES {
+            return Err(ContractError::InvalidDecimals {});
+        }
+        DECIMALS.save(deps.storage, &asset.check(deps.api, None)?, &decimals)?;
+    }
+    if from.is_none() {
+        migrate_legacy_bond(deps.branch())?;
+        migrate_raw_prices(deps)?;
+    }
+    Ok(())
+}
+
+/// Turns the single bond of the contract into market 0 of the depository,
+/// with a note for the bond of each address
+fn migrate_legacy_bond(deps: DepsMut) -> Result<(), ContractError> {
+    let config = LEGACY_CONFIG.load(deps.storage)?;
+
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(config.admin.as_str()))?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            staking: config.staking,
+            treasury: config.treasury,
+            guardian: None,
+            note_nft: None,
+            dao_fee: Decimal256::zero(),
+            dao_address: None,
+            lock_contract: None,
+        },
+    )?;
+
+    MARKETS.save(
+        deps.storage,
+        0,
+        &Market {
+            quote_token: AssetInfo::native(config.principle),
+            capacity_in_quote: false,
+            capacity: Uint128::MAX,
+            total_debt: LEGACY_TOTAL_DEBT.load(deps.storage)?,
+            last_decay: LEGACY_LAST_DECAY.load(deps.storage)?,
+            sold: Uint128::zero(),
+            purchased: Uint128::zero(),
+            oracle: None,
+            calculator: None,
+            allow_list: None,
+        },
+    )?;
+    TERMS.save(deps.storage, 0, &LEGACY_TERMS.load(deps.storage)?.into())?;
+    ADJUSTMENTS.save(deps.storage, 0, &LEGACY_ADJUSTMENT.load(deps.storage)?)?;
+    NEXT_MARKET_ID.save(deps.storage, &1)?;
+
+    let bonds = LEGACY_BOND_INFO
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut next_note_id = 0;
+    for (addr, bond) in bonds {
+        LEGACY_BOND_INFO.remove(deps.storage, &addr);
+        NOTES.save(
+            deps.storage,
+            (&addr, next_note_id),
+            &Note {
+                market_id: 0,
+                payout: bond.payout,
+                vesting_time_left: bond.vesting_time_left,
+                last_time: bond.last_time,
+            },
+        )?;
+        next_note_id += 1;
+    }
+    NEXT_NOTE_ID.save(deps.storage, &next_note_id)?;
+
+    LEGACY_TERMS.remove(deps.storage);
+    LEGACY_TOTAL_DEBT.remove(deps.storage);
+    LEGACY_LAST_DECAY.remove(deps.storage);
+    LEGACY_ADJUSTMENT.remove(deps.storage);
+
+    Ok(())
+}
+
+/// Turns the prices of every market from quote atomics per OHM atomic into whole quote tokens
+/// per whole OHM, along with the adjustments of their control variable.
+/// Calculator markets were already priced in OHM decimals
+fn migrate_raw_prices(deps: DepsMut) -> Result<(), ContractError> {
+    let markets = MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (market_id, market) in markets {
+        let decimals = match market.calculator {
+            Some(_) => OHM_DECIMALS,
+            None => quote_decimals(deps.as_ref(), &market.quote_token)?,
+        };
+        if decimals == OHM_DECIMALS {
+            continue;
+        }
+        // 10^(OHM_DECIMALS - decimals)
+        let scale = Decimal256::from_atomics(10u128.pow(OHM_DECIMALS.into()), decimals.into())?;
+
+        let mut terms = TERMS.load(deps.storage, market_id)?;
+        terms.control_variable *= scale;
+        terms.minimum_price *= scale;
+        if let PricingModel::SequentialDutchAuction(params) = &mut terms.pricing {
+            params.initial_price *= scale;
+        }
+        TERMS.save(deps.storage, market_id, &terms)?;
+
+        if let Some(mut adjustment) = ADJUSTMENTS.may_load(deps.storage, market_id)? {
+            adjustment.rate *= scale;
+            adjustment.target *= scale;
+            ADJUSTMENTS.save(deps.storage, market_id, &adjustment)?;
+        }
+        if let Some(mut steps) = ADJUSTMENT_SCHEDULES.may_load(deps.storage, market_id)? {
+            for step in &mut steps {
+                step.rate *= scale;
+                step.target *= scale;
+            }
+            ADJUSTMENT_SCHEDULES.save(deps.storage, market_id, &steps)?;
+        }
+        if let Some(mut auction) = AUCTIONS.may_load(deps.storage, market_id)? {
+            auction.price *= scale;
+            AUCTIONS.save(deps.storage, market_id, &auction)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use cosmwasm_std::testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage};
+    use cosmwasm_std::{Addr, Decimal256, Order, OwnedDeps, StdResult, Storage, Uint128};
+    use cw_asset::{AssetInfo, AssetInfoUnchecked};
+
+    use crate::contract::{migrate, CONTRACT_NAME, CONTRACT_VERSION};
+    use crate::helpers::adjust;
+    use crate::msg::MigrateMsg;
+    use crate::state::{
+        Note, VestingMode, ADJUSTMENTS, CONFIG, MARKETS, NEXT_MARKET_ID, NEXT_NOTE_ID, NOTES, TERMS,
+    };
+    use crate::ContractError;
+
+    /// Bond written by the code released before cw2 versioning, selling OHM for INJ to two
+    /// bonders. Its prices are in INJ atomics per OHM atomic: 20 INJ per OHM with a 10 INJ
+    /// minimum, raised by 1 INJ per deposit up to 30 INJ
+    fn legacy_deps() -> OwnedDeps<MockStorage, MockApi, MockQuerier> {
+        let mut deps = mock_dependencies();
+        let now = mock_env().block.time.nanos();
+        deps.storage.set(
+            b"config",
+            br#"{"principle":"inj","admin":"admin","staking":"staking","treasury":"treasury"}"#,
+        );
+        deps.storage.set(
+            b"terms",
+            concat!(
+                r#"{"control_variable":"20000000000000","minimum_price":"10000000000000","#,
+                r#""max_payout":"0.01","max_debt":"1000000","vesting_term":432000}"#
+            )
+            .as_bytes(),
+        );
+        deps.storage.set(b"total_debt", br#""5000""#);
+        deps.storage
+            .set(b"last_decay", format!(r#""{now}""#).as_bytes());
+        deps.storage.set(
+            b"adjustment",
+            format!(
+                concat!(
+                    r#"{{"add":true,"rate":"1000000000000","target":"30000000000000","#,
+                    r#""buffer":0,"last_time":"{}"}}"#
+                ),
+                now
+            )
+            .as_bytes(),
+        );
+        for (addr, payout) in [("alice", 100), ("bob", 200)] {
+            // Map keys are the length prefixed namespace followed by the address
+            deps.storage.set(
+                &[&b"\x00\x09bond_info"[..], addr.as_bytes()].concat(),
+                format!(
+                    r#"{{"payout":"{}","vesting_time_left":1000,"last_time":"{}"}}"#,
+                    payout, now
+                )
+                .as_bytes(),
+            );
+        }
+        deps
+    }
+
+    fn inj_decimals() -> MigrateMsg {
+        MigrateMsg {
+            decimals: vec![(AssetInfoUnchecked::native("inj"), 18)],
+        }
+    }
+
+    #[test]
+    pub fn legacy_bond_becomes_the_first_market() -> anyhow::Result<()> {
+        let mut deps = legacy_deps();
+        let now = mock_env().block.time;
+
+        migrate(deps.as_mut(), mock_env(), inj_decimals())?;
+
+        let version = cw2::get_contract_version(&deps.storage)?;
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
+        assert_eq!(
+            cw_ownable::get_ownership(&deps.storage)?.owner,
+            Some(Addr::unchecked("admin"))
+        );
+        let config = CONFIG.load(&deps.storage)?;
+        assert_eq!(config.staking, Addr::unchecked("staking"));
+        assert_eq!(config.treasury, Addr::unchecked("treasury"));
+
+        assert_eq!(NEXT_MARKET_ID.load(&deps.storage)?, 1);
+        let market = MARKETS.load(&deps.storage, 0)?;
+        assert_eq!(market.quote_token, AssetInfo::native("inj"));
+        assert_eq!(market.total_debt, Uint128::new(5_000));
+        assert_eq!(market.last_decay, now);
+        assert!(market.is_open());
+        let terms = TERMS.load(&deps.storage, 0)?;
+        assert_eq!(terms.vesting, VestingMode::FixedTerm(432_000));
+        assert_eq!(terms.max_debt, Uint128::new(1_000_000));
+
+        // Each bond is now a note
+        let notes = NOTES
+            .range(&deps.storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()?;
+        let note = |payout| Note {
+            market_id: 0,
+            payout: Uint128::new(payout),
+            vesting_time_left: 1000,
+            last_time: now,
+        };
+        assert_eq!(
+            notes,
+            vec![
+                ((Addr::unchecked("alice"), 0), note(100)),
+                ((Addr::unchecked("bob"), 1), note(200)),
+            ]
+        );
+        assert_eq!(NEXT_NOTE_ID.load(&deps.storage)?, 2);
+
+        // Only the config key is reused
+        for key in ["terms", "total_debt", "last_decay", "adjustment"] {
+            assert_eq!(deps.storage.get(key.as_bytes()), None);
+        }
+        assert_eq!(deps.storage.get(b"\x00\x09bond_infoalice"), None);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn migrating_rescales_active_adjustments() -> anyhow::Result<()> {
+        let mut deps = legacy_deps();
+
+        migrate(deps.as_mut(), mock_env(), inj_decimals())?;
+
+        let terms = TERMS.load(&deps.storage, 0)?;
+        assert_eq!(terms.control_variable, Decimal256::from_str("20")?);
+        assert_eq!(terms.minimum_price, Decimal256::from_str("10")?);
+        let adjustment = ADJUSTMENTS.load(&deps.storage, 0)?;
+        assert_eq!(adjustment.rate, Decimal256::one());
+        assert_eq!(adjustment.target, Decimal256::from_str("30")?);
+
+        // The next deposit moves the control variable by one whole INJ
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(1);
+        adjust(deps.as_mut(), env, 0)?;
+        assert_eq!(
+            TERMS.load(&deps.storage, 0)?.control_variable,
+            Decimal256::from_str("21")?
+        );
+        assert_eq!(ADJUSTMENTS.load(&deps.storage, 0)?.rate, Decimal256::one());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn migrating_needs_the_quote_decimals() -> anyhow::Result<()> {
+        let mut deps = legacy_deps();
+
+        let err = migrate(deps.as_mut(), mock_env(), MigrateMsg { decimals: vec![] }).unwrap_err();
+        assert!(matches!(err, ContractError::UnknownDecimals { .. }));
+
+        Ok(())
+    }
+}
@@ -1,87 +1,374 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal256, Uint128};
+use cosmwasm_std::{
+    to_json_binary, Binary, CosmosMsg, Decimal256, StdResult, Timestamp, Uint128, WasmMsg,
+};
+use cw20::Cw20ReceiveMsg;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
 
-use crate::state::{Adjustment, Bond, Terms};
+use crate::state::{
+    Adjustment, AdjustmentStep, AllowList, AutoRedeem, CalculatorConfig, HistoryEntry, Market,
+    Note, Operation, OracleConfig, PauseStatus, Terms, TuningConfig,
+};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub principle: String,
-    pub admin: Option<String>,
+    pub owner: Option<String>,
     pub staking: String,
-    pub terms: Terms,
     pub treasury: String,
+    pub guardian: Option<String>,
 }
 
 /// Message type for `execute` entry_point
+#[cw_ownable_execute]
 #[cw_serde]
 #[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
+    /// Pays the vested part of the recipient's notes, all of them if `note_ids` is empty
     Redeem {
         recipient: String,
         stake: bool,
+        note_ids: Option<Vec<u64>>,
     },
+    /// `referrer` is the whitelisted frontend earning referral rewards on the payout.
+    /// `proof` shows that the depositor is allowed in a private market.
+    /// The `DepositResponse` is set as data, and sent back to the sender with `callback`
     #[cfg_attr(feature = "interface", payable)]
     Deposit {
+        market_id: u64,
         max_price: Decimal256,
         depositor: String,
+        referrer: Option<String>,
+        proof: Option<Vec<String>>,
+        callback: Option<Binary>,
+    },
+    /// Redeems the notes of the recipients that enabled auto redeem, the others are skipped.
+    /// The caller earns their tips, staked if `stake` is set
+    RedeemFor {
+        recipients: Vec<String>,
+        stake: bool,
+    },
+    /// Lets anyone redeem the sender's notes through `RedeemFor` for a share of the payouts
+    SetAutoRedeem {
+        stake: bool,
+        tip: Decimal256,
+    },
+    DisableAutoRedeem {},
+    /// Deposits in markets quoted in a cw20 token, see [`ReceiveMsg`]
+    Receive(Cw20ReceiveMsg),
+    /// Opens a market selling OHM for `quote_token`
+    CreateMarket {
+        quote_token: cw_asset::AssetInfoBase<String>,
+        capacity_in_quote: bool,
+        capacity: Uint128,
+        terms: Terms,
+        oracle: Option<OracleConfig>,
+        /// Values the deposits of LP markets
+        calculator: Option<CalculatorConfig>,
+        /// Concludes the market and tunes it to sell its capacity evenly until then
+        tuning: Option<TuningConfig>,
+        /// Restricts the market to the addresses of the list
+        allow_list: Option<AllowList>,
+    },
+    /// Stops all new deposits, existing bonds can still be redeemed
+    CloseMarket {
+        market_id: u64,
+    },
+    /// Instantiates the cw721 contract of the notes, with the depository as minter
+    InstantiateNoteNft {
+        code_id: u64,
+        name: String,
+        symbol: String,
+    },
+    /// Mints the sender's note as an NFT, it is redeemed by whoever owns the token
+    TokenizeNote {
+        note_id: u64,
     },
     UpdateTerms {
+        market_id: u64,
         terms: Terms,
     },
     UpdateConfig {
-        principle: Option<String>,
-        admin: Option<String>,
         staking: Option<String>,
         treasury: Option<String>,
+        guardian: Option<String>,
+        /// Share of each payout minted to the DAO, it needs a DAO address
+        dao_fee: Option<Decimal256>,
+        dao_address: Option<String>,
+        lock_contract: Option<String>,
     },
     UpdateAdjustment {
+        market_id: u64,
         add: Option<bool>,
         rate: Option<Decimal256>,
         target: Option<Decimal256>,
         buffer: Option<u64>,
     },
+    /// Whitelists a frontend with its referral rate, a share of the payouts it refers.
+    /// Removes it from the whitelist when `rate` is `None`, its accrued rewards can still be claimed
+    SetReferrer {
+        referrer: String,
+        rate: Option<Decimal256>,
+    },
+    /// Sends the referral rewards accrued by the sender
+    ClaimReferralRewards {},
+    /// Overrides the decimals of a quote token, the queried ones are used again when `None`
+    SetDecimals {
+        asset: cw_asset::AssetInfoUnchecked,
+        decimals: Option<u8>,
+    },
+    /// Replaces the adjustment schedule of the market, the steps follow the current adjustment
+    ScheduleAdjustments {
+        market_id: u64,
+        steps: Vec<AdjustmentStep>,
+    },
+    /// Removes the guardian, only the owner can pause afterwards
+    RemoveGuardian {},
+    /// Can be called by the guardian or the owner
+    Pause {
+        operations: Vec<Operation>,
+    },
+    /// Can only be called by the owner
+    Unpause {
+        operations: Vec<Operation>,
+    },
+}
+
+/// Hook messages of the cw20 quote tokens
+#[cw_serde]
+pub enum ReceiveMsg {
+    /// The callback is sent to the cw20 sender
+    Deposit {
+        market_id: u64,
+        max_price: Decimal256,
+        depositor: String,
+        referrer: Option<String>,
+        proof: Option<Vec<String>>,
+        callback: Option<Binary>,
+    },
+}
+
+/// Result of a deposit, set as the data of the response
+#[cw_serde]
+pub struct DepositResponse {
+    /// Amount vesting for the depositor, after the deposit fee of the lock contract
+    /// for locked payouts
+    pub payout: Uint128,
+    pub vesting_end: Timestamp,
+    /// `None` when the payout was locked in the lock contract
+    pub note_id: Option<u64>,
+    /// Price paid for the `payout`, checked against `max_price`
+    pub price: Decimal256,
+}
+
+/// Executed on the sender of a deposit that has a callback
+#[cw_serde]
+pub struct DepositCallbackMsg {
+    pub deposit: DepositResponse,
+    pub msg: Binary,
+}
+
+impl DepositCallbackMsg {
+    /// Serializes the message wrapped in the `DepositCallback` variant of the receiver
+    pub fn into_binary(self) -> StdResult<Binary> {
+        to_json_binary(&DepositCallbackExecuteMsg::DepositCallback(self))
+    }
+
+    pub fn into_cosmos_msg(self, contract_addr: impl Into<String>) -> StdResult<CosmosMsg> {
+        Ok(WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg: self.into_binary()?,
+            funds: vec![],
+        }
+        .into())
+    }
+}
+
+/// The variant receivers have to add to their `ExecuteMsg` to handle deposit callbacks
+#[cw_serde]
+pub enum DepositCallbackExecuteMsg {
+    DepositCallback(DepositCallbackMsg),
+}
+
+/// Message type for `sudo` entry_point, only the chain governance can call it
+#[cw_serde]
+pub enum SudoMsg {
+    /// Replaces the owner without going through the two-step transfer
+    UpdateOwner {
+        owner: String,
+    },
+    Pause {
+        operations: Vec<Operation>,
+    },
+    Unpause {
+        operations: Vec<Operation>,
+    },
+    /// Sends all the funds held by the contract to the recovery address
+    SweepFunds {
+        recipient: String,
+    },
 }
 
 /// Message type for `migrate` entry_point
 #[cw_serde]
-pub enum MigrateMsg {}
+pub struct MigrateMsg {
+    /// Decimals of quote tokens, saved as with `SetDecimals` before the prices are rescaled
+    pub decimals: Vec<(cw_asset::AssetInfoUnchecked, u8)>,
+}
 
 /// Message type for `query` entry_point
+#[cw_ownable_query]
 #[cw_serde]
 #[derive(QueryResponses)]
 #[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
 pub enum QueryMsg {
     #[returns(ConfigResponse)]
     Config {},
+    #[returns(Vec<MarketResponse>)]
+    Markets {},
+    #[returns(MarketResponse)]
+    Market { id: u64 },
     #[returns(Terms)]
-    Terms {},
+    Terms { market_id: u64 },
     #[returns(Adjustment)]
-    Adjustment {},
+    Adjustment { market_id: u64 },
+    /// Adjustment steps that haven't started yet
+    #[returns(Vec<AdjustmentStep>)]
+    AdjustmentSchedule { market_id: u64 },
+    #[returns(MarketTuningResponse)]
+    MarketTuning { market_id: u64 },
+    #[returns(Uint128)]
+    MaxPayout { market_id: u64 },
+    /// Value of `amount` quote tokens, as used by `PayoutFor`
     #[returns(Uint128)]
-    MaxPayout {},
+    QuoteValue { market_id: u64, amount: Uint128 },
     #[returns(Uint128)]
-    PayoutFor { value: Uint128 },
+    PayoutFor { market_id: u64, value: Uint128 },
+    /// Whole quote tokens paid for one whole OHM
     #[returns(Decimal256)]
-    BondPrice {},
+    BondPrice { market_id: u64 },
+    /// Price of one OHM in quote tokens according to the market oracle
     #[returns(Decimal256)]
-    DebtRatio {},
-    #[returns(Uint128)]
-    CurrentDebt {},
+    MarketPrice { market_id: u64 },
+    #[returns(BondDiscountResponse)]
+    BondDiscount { market_id: u64 },
     #[returns(Decimal256)]
-    DebtDecay {},
+    DebtRatio { market_id: u64 },
+    #[returns(Uint128)]
+    CurrentDebt { market_id: u64 },
     #[returns(Decimal256)]
-    PercentVestedFor { recipient: String },
+    DebtDecay { market_id: u64 },
     #[returns(Decimal256)]
+    PercentVestedFor { recipient: String, note_id: u64 },
+    /// Sum of the pending payouts over all notes
+    #[returns(Uint128)]
     PendingPayoutFor { recipient: String },
-    #[returns(Bond)]
-    BondInfo { recipient: String },
+    #[returns(Note)]
+    Note { owner: String, note_id: u64 },
+    #[returns(Vec<NoteResponse>)]
+    Notes {
+        owner: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    #[returns(Note)]
+    TokenizedNote { note_id: u64 },
+    /// Whether `address` can deposit in the market, all addresses can deposit in public markets
+    #[returns(bool)]
+    IsAllowed {
+        market_id: u64,
+        address: String,
+        proof: Vec<String>,
+    },
+    /// Deposits and redemptions of the address, oldest first
+    #[returns(Vec<HistoryResponse>)]
+    DepositHistory {
+        address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// Totals of every market
+    #[returns(Vec<MarketStatsResponse>)]
+    MarketStats {},
+    #[returns(Option<AutoRedeem>)]
+    AutoRedeem { address: String },
+    /// Addresses that enabled auto redeem, for the keepers
+    #[returns(Vec<AutoRedeemerResponse>)]
+    AutoRedeemers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Decimals used to price the quote token
+    #[returns(u8)]
+    Decimals { asset: cw_asset::AssetInfoUnchecked },
+    /// OHM accrued by the referrer that can be claimed
+    #[returns(Uint128)]
+    ReferralRewards { referrer: String },
+    #[returns(PauseStatus)]
+    PauseStatus {},
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
-    pub principle: String,
-    pub admin: String,
     pub staking: String,
     pub treasury: String,
+    pub guardian: Option<String>,
+    pub note_nft: Option<String>,
+    pub dao_fee: Decimal256,
+    pub dao_address: Option<String>,
+    pub lock_contract: Option<String>,
+}
+
+#[cw_serde]
+pub struct NoteResponse {
+    pub id: u64,
+    pub note: Note,
+    /// Vested payout that can be redeemed now
+    pub pending_payout: Uint128,
+}
+
+#[cw_serde]
+pub struct MarketResponse {
+    pub id: u64,
+    pub market: Market,
+    pub terms: Terms,
+}
+
+#[cw_serde]
+pub struct MarketTuningResponse {
+    pub config: TuningConfig,
+    pub last_tune: Timestamp,
+    /// The market is tuned again on the first deposit from this time
+    pub next_tune: Timestamp,
+}
+
+/// Discount of the bond price relative to the market price.
+/// `negative` is set when the bond is priced above the market
+#[cw_serde]
+pub struct BondDiscountResponse {
+    pub discount: Decimal256,
+    pub negative: bool,
+}
+
+#[cw_serde]
+pub struct HistoryResponse {
+    pub id: u64,
+    pub entry: HistoryEntry,
+}
+
+#[cw_serde]
+pub struct MarketStatsResponse {
+    pub market_id: u64,
+    /// Quote tokens received by the market
+    pub total_principle: Uint128,
+    /// OHM paid out by the market
+    pub total_payout: Uint128,
+    /// Quote tokens paid for one OHM on average, zero before the first deposit
+    pub average_price: Decimal256,
+}
+
+#[cw_serde]
+pub struct AutoRedeemerResponse {
+    pub address: String,
+    pub preference: AutoRedeem,
 }
@@ -1,13 +1,34 @@
-use cosmwasm_std::{to_json_binary, Addr, Decimal256, Deps, Env, SupplyResponse, Uint128, Uint256};
+use std::str::FromStr;
+
+use bond_note::msg::{OwnerOfResponse, TokensResponse};
+use cosmwasm_std::{
+    to_json_binary, Addr, Decimal256, Deps, Env, Order, StdError, StdResult, SupplyResponse,
+    Uint128, Uint256,
+};
+use cw20::{Cw20QueryMsg, TokenInfoResponse};
+use cw_asset::AssetInfo;
+use cw_storage_plus::Bound;
+use injective_std::types::injective::{
+    exchange::v1beta1::ExchangeQuerier, oracle::v1beta1::OracleQuerier,
+};
+use oracle::msg::PriceResponse;
 
 use crate::{
     execute::current_debt,
-    state::{query_bond_price, Adjustment, Bond, Terms, ADJUSTMENT, BOND_INFO, CONFIG, TERMS},
+    msg::{
+        AutoRedeemerResponse, BondDiscountResponse, HistoryResponse, MarketResponse,
+        MarketStatsResponse, MarketTuningResponse, NoteResponse,
+    },
+    state::{
+        load_market, query_bond_price, Adjustment, AdjustmentStep, AutoRedeem, Note, OracleConfig,
+        OracleSource, Terms, ADJUSTMENTS, ADJUSTMENT_SCHEDULES, AUTO_REDEEM, CONFIG, DECIMALS,
+        HISTORY, MARKETS, NOTES, REFERRAL_REWARDS, TERMS, TOKENIZED_NOTES, TUNINGS,
+    },
     ContractError,
 };
-use staking_contract::msg::ConfigResponse;
+use staking_contract::{msg::ConfigResponse, state::OHM_DECIMALS};
 
-pub fn total_base_supply(deps: Deps) -> Result<Uint128, ContractError> {
+pub fn ohm_denom(deps: Deps) -> Result<String, ContractError> {
     let config = CONFIG.load(deps.storage)?;
     let staking_config: ConfigResponse = deps.querier.query(&cosmwasm_std::QueryRequest::Wasm(
         cosmwasm_std::WasmQuery::Smart {
@@ -15,19 +36,23 @@ pub fn total_base_supply(deps: Deps) -> Result<Uint128, ContractError> {
             msg: to_json_binary(&staking_contract::msg::QueryMsg::Config {})?,
         },
     ))?;
+    Ok(staking_config.ohm_denom)
+}
+
+pub fn total_base_supply(deps: Deps) -> Result<Uint128, ContractError> {
     let supply: SupplyResponse = deps.querier.query(&cosmwasm_std::QueryRequest::Bank(
         cosmwasm_std::BankQuery::Supply {
-            denom: staking_config.ohm_denom,
+            denom: ohm_denom(deps)?,
         },
     ))?;
     Ok(supply.amount.amount)
 }
 
-pub fn debt_ratio(deps: Deps, env: Env) -> Result<Decimal256, ContractError> {
+pub fn debt_ratio(deps: Deps, env: Env, market_id: u64) -> Result<Decimal256, ContractError> {
     let base_supply = total_base_supply(deps)?;
 
     Ok(Decimal256::from_ratio(
-        current_debt(deps, env)?,
+        current_debt(deps, env, market_id)?,
         base_supply,
     ))
 }
@@ -40,25 +65,219 @@ pub fn query_config(deps: Deps) -> Result<crate::msg::ConfigResponse, ContractEr
     let config = CONFIG.load(deps.storage)?;
 
     Ok(crate::msg::ConfigResponse {
-        principle: config.principle,
-        admin: config.admin.to_string(),
         staking: config.staking.to_string(),
         treasury: config.treasury.to_string(),
+        guardian: config.guardian.map(|g| g.to_string()),
+        note_nft: config.note_nft.map(|n| n.to_string()),
+        dao_fee: config.dao_fee,
+        dao_address: config.dao_address.map(|d| d.to_string()),
+        lock_contract: config.lock_contract.map(|l| l.to_string()),
+    })
+}
+
+pub fn query_market(deps: Deps, market_id: u64) -> Result<MarketResponse, ContractError> {
+    Ok(MarketResponse {
+        id: market_id,
+        market: load_market(deps.storage, market_id)?,
+        terms: TERMS.load(deps.storage, market_id)?,
+    })
+}
+
+pub fn query_markets(deps: Deps) -> Result<Vec<MarketResponse>, ContractError> {
+    MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|market| {
+            let (id, market) = market?;
+            Ok(MarketResponse {
+                id,
+                market,
+                terms: TERMS.load(deps.storage, id)?,
+            })
+        })
+        .collect()
+}
+
+pub fn query_terms(deps: Deps, market_id: u64) -> Result<Terms, ContractError> {
+    load_market(deps.storage, market_id)?;
+    Ok(TERMS.load(deps.storage, market_id)?)
+}
+
+pub fn query_adjustment(deps: Deps, market_id: u64) -> Result<Adjustment, ContractError> {
+    load_market(deps.storage, market_id)?;
+    Ok(ADJUSTMENTS.load(deps.storage, market_id)?)
+}
+
+pub fn query_adjustment_schedule(
+    deps: Deps,
+    market_id: u64,
+) -> Result<Vec<AdjustmentStep>, ContractError> {
+    load_market(deps.storage, market_id)?;
+    Ok(ADJUSTMENT_SCHEDULES
+        .may_load(deps.storage, market_id)?
+        .unwrap_or_default())
+}
+
+pub fn query_market_tuning(
+    deps: Deps,
+    market_id: u64,
+) -> Result<MarketTuningResponse, ContractError> {
+    load_market(deps.storage, market_id)?;
+    let tuning = TUNINGS
+        .may_load(deps.storage, market_id)?
+        .ok_or(ContractError::NoTuning { market_id })?;
+    Ok(MarketTuningResponse {
+        next_tune: tuning.next_tune(),
+        config: tuning.config,
+        last_tune: tuning.last_tune,
     })
 }
 
-pub fn query_terms(deps: Deps) -> Result<Terms, ContractError> {
-    Ok(TERMS.load(deps.storage)?)
+/// Price of one OHM in quote tokens, as quoted by the market oracle
+pub fn market_price(deps: Deps, market_id: u64) -> Result<Decimal256, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    let oracle = market.oracle.as_ref().ok_or(ContractError::NoOracle {})?;
+
+    oracle_price(deps, oracle, ohm_denom(deps)?, market.quote_id())
+}
+
+/// Price of one whole `base` in whole `quote` tokens.
+/// The Injective source uses its own symbols instead
+pub fn oracle_price(
+    deps: Deps,
+    oracle: &OracleConfig,
+    base: String,
+    quote: String,
+) -> Result<Decimal256, ContractError> {
+    let price = match &oracle.source {
+        OracleSource::Contract { address } => {
+            let price: PriceResponse = deps
+                .querier
+                .query_wasm_smart(address, &oracle::msg::QueryMsg::Price { base, quote })?;
+            price.rate
+        }
+        OracleSource::Injective {
+            oracle_type,
+            base,
+            quote,
+        } => {
+            let price = OracleQuerier::new(&deps.querier)
+                .oracle_price(*oracle_type, base.clone(), quote.clone())?
+                .price_pair_state
+                .ok_or(ContractError::NoOraclePrice {})?;
+            Decimal256::from_str(&price.pair_price)?
+        }
+    };
+
+    if price.is_zero() {
+        return Err(ContractError::NoOraclePrice {});
+    }
+    Ok(price)
+}
+
+pub fn bond_discount(
+    deps: Deps,
+    env: Env,
+    market_id: u64,
+) -> Result<BondDiscountResponse, ContractError> {
+    Ok(discount(
+        query_bond_price(deps, env, market_id)?,
+        market_price(deps, market_id)?,
+    ))
 }
 
-pub fn query_adjustment(deps: Deps) -> Result<Adjustment, ContractError> {
-    Ok(ADJUSTMENT.load(deps.storage)?)
+/// `market_price` must not be zero
+pub fn discount(bond_price: Decimal256, market_price: Decimal256) -> BondDiscountResponse {
+    if bond_price > market_price {
+        BondDiscountResponse {
+            discount: (bond_price - market_price) / market_price,
+            negative: true,
+        }
+    } else {
+        BondDiscountResponse {
+            discount: (market_price - bond_price) / market_price,
+            negative: false,
+        }
+    }
+}
+
+/// Value of `amount` quote tokens, LP tokens are valued by the bonding calculator of the market
+pub fn quote_value(deps: Deps, market_id: u64, amount: Uint128) -> Result<Uint128, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    match market.calculator {
+        Some(calculator) => Ok(deps.querier.query_wasm_smart(
+            calculator.calculator,
+            &bonding_calculator::msg::QueryMsg::Valuation {
+                pool: calculator.pool,
+                amount,
+            },
+        )?),
+        None => Ok(amount),
+    }
 }
 
-pub fn payout_for(deps: Deps, env: Env, value: Uint128) -> Result<Uint128, ContractError> {
-    let payout = Decimal256::from_ratio(value, 1u128) / query_bond_price(deps, env)?;
+/// OHM paid for `value` quote tokens, the bond price is in whole quote tokens per whole OHM.
+/// Markets with a bonding calculator are priced in its valuation, which is in OHM precision
+pub fn payout_for(
+    deps: Deps,
+    env: Env,
+    market_id: u64,
+    value: Uint128,
+) -> Result<Uint128, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    let value_decimals = match market.calculator {
+        Some(_) => OHM_DECIMALS,
+        None => quote_decimals(deps, &market.quote_token)?,
+    };
+    let value = to_human(value, value_decimals)?;
 
-    Ok((payout * Uint256::one()).try_into()?)
+    from_human(
+        value / query_bond_price(deps, env, market_id)?,
+        OHM_DECIMALS,
+    )
+}
+
+/// Decimals of a quote token. Unless the owner set them, they are queried from the exchange
+/// module for native denoms and from the token info for cw20 tokens
+pub fn quote_decimals(deps: Deps, asset: &AssetInfo) -> Result<u8, ContractError> {
+    if let Some(decimals) = DECIMALS.may_load(deps.storage, asset)? {
+        return Ok(decimals);
+    }
+
+    let unknown = || ContractError::UnknownDecimals {
+        asset: asset.clone(),
+    };
+    let decimals = match asset {
+        AssetInfo::Native(denom) => {
+            ExchangeQuerier::new(&deps.querier)
+                .denom_decimal(denom.clone())
+                .map_err(|_| unknown())?
+                .decimal
+        }
+        AssetInfo::Cw20(address) => {
+            let info: TokenInfoResponse = deps
+                .querier
+                .query_wasm_smart(address, &Cw20QueryMsg::TokenInfo {})?;
+            info.decimals.into()
+        }
+        _ => return Err(unknown()),
+    };
+    // Tokens without decimals are unknown to the exchange module, the owner sets them if needed
+    match u8::try_from(decimals) {
+        Ok(decimals) if decimals != 0 && u32::from(decimals) <= Decimal256::DECIMAL_PLACES => {
+            Ok(decimals)
+        }
+        _ => Err(unknown()),
+    }
+}
+
+/// `amount` in whole tokens
+pub fn to_human(amount: Uint128, decimals: u8) -> Result<Decimal256, ContractError> {
+    Ok(Decimal256::from_atomics(amount, decimals.into())?)
+}
+
+/// `amount` whole tokens in the smallest unit, rounded down
+pub fn from_human(amount: Decimal256, decimals: u8) -> Result<Uint128, ContractError> {
+    Ok((Uint256::from(10u128.pow(decimals.into())) * amount).try_into()?)
 }
 
 pub fn pending_payout_for(
@@ -67,21 +286,67 @@ pub fn pending_payout_for(
     recipient: String,
 ) -> Result<Uint128, ContractError> {
     let recipient_addr = deps.api.addr_validate(&recipient)?;
-    let percent_vested = percent_vested_for(deps, env, &recipient_addr)?;
-    let bond = BOND_INFO
-        .load(deps.storage, &recipient_addr)
-        .unwrap_or_default();
+    let mut notes = NOTES
+        .prefix(&recipient_addr)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, note)| note))
+        .collect::<StdResult<Vec<_>>>()?;
+    for note_id in tokenized_note_ids(deps, &recipient_addr)? {
+        // Tokens minted before fully vested notes were burned have no note left
+        if let Some(note) = TOKENIZED_NOTES.may_load(deps.storage, note_id)? {
+            notes.push(note);
+        }
+    }
+
+    notes.into_iter().try_fold(Uint128::zero(), |total, note| {
+        Ok::<_, ContractError>(total + pending_payout(&note, &env)?)
+    })
+}
+
+/// Ids of the tokenized notes held by `owner`
+pub fn tokenized_note_ids(deps: Deps, owner: &Addr) -> Result<Vec<u64>, ContractError> {
+    let Some(note_nft) = CONFIG.load(deps.storage)?.note_nft else {
+        return Ok(vec![]);
+    };
+
+    let mut note_ids = vec![];
+    let mut start_after = None;
+    loop {
+        let page: TokensResponse = deps.querier.query_wasm_smart(
+            &note_nft,
+            &bond_note::msg::QueryMsg::Tokens {
+                owner: owner.to_string(),
+                start_after,
+                limit: Some(TOKENS_PAGE_LIMIT),
+            },
+        )?;
+        for token_id in &page.tokens {
+            note_ids.push(
+                token_id
+                    .parse()
+                    .map_err(|_| StdError::parse_err("u64", token_id))?,
+            );
+        }
+        if page.tokens.len() < TOKENS_PAGE_LIMIT as usize {
+            return Ok(note_ids);
+        }
+        start_after = page.tokens.last().cloned();
+    }
+}
 
-    if percent_vested > Decimal256::one() {
-        Ok(bond.payout)
+/// Vested part of the note payout
+pub fn pending_payout(note: &Note, env: &Env) -> Result<Uint128, ContractError> {
+    let percent_vested = percent_vested(note, env);
+    if percent_vested >= Decimal256::one() {
+        Ok(note.payout)
     } else {
-        Ok((Uint256::from(bond.payout) * percent_vested).try_into()?)
+        Ok((Uint256::from(note.payout) * percent_vested).try_into()?)
     }
 }
 
-pub fn max_payout(deps: Deps) -> Result<Uint128, ContractError> {
+pub fn max_payout(deps: Deps, market_id: u64) -> Result<Uint128, ContractError> {
     let base_supply = total_base_supply(deps)?;
-    let terms = TERMS.load(deps.storage)?;
+    let terms = TERMS.load(deps.storage, market_id)?;
 
     Ok((Uint256::from(base_supply) * terms.max_payout).try_into()?)
 }
@@ -90,20 +355,178 @@ pub fn percent_vested_for(
     deps: Deps,
     env: Env,
     recipient: &Addr,
+    note_id: u64,
 ) -> Result<Decimal256, ContractError> {
-    let bond = BOND_INFO.load(deps.storage, recipient)?;
-    // Bond memory bond = bondInfo[ _depositor ];
-    let seconds_since_last = env.block.time.seconds() - bond.last_time.seconds();
-    let vesting = bond.vesting_time_left;
+    let note = load_note(deps, recipient, note_id)?;
+    Ok(percent_vested(&note, &env))
+}
+
+/// Notes without vesting time left are fully vested
+pub fn percent_vested(note: &Note, env: &Env) -> Decimal256 {
+    let seconds_since_last = env.block.time.seconds() - note.last_time.seconds();
+    let vesting = note.vesting_time_left;
     if vesting != 0 {
-        Ok(Decimal256::from_ratio(seconds_since_last, vesting))
+        Decimal256::from_ratio(seconds_since_last, vesting)
     } else {
-        Ok(Decimal256::zero())
+        Decimal256::one()
     }
 }
 
-pub fn bond_info(deps: Deps, recipient: String) -> Result<Bond, ContractError> {
-    Ok(BOND_INFO
-        .load(deps.storage, &deps.api.addr_validate(&recipient)?)
+pub fn load_note(deps: Deps, owner: &Addr, note_id: u64) -> Result<Note, ContractError> {
+    NOTES
+        .may_load(deps.storage, (owner, note_id))?
+        .ok_or(ContractError::NoteNotFound { note_id })
+}
+
+pub fn query_note(deps: Deps, owner: String, note_id: u64) -> Result<Note, ContractError> {
+    load_note(deps, &deps.api.addr_validate(&owner)?, note_id)
+}
+
+pub fn query_tokenized_note(deps: Deps, note_id: u64) -> Result<Note, ContractError> {
+    TOKENIZED_NOTES
+        .may_load(deps.storage, note_id)?
+        .ok_or(ContractError::NoteNotFound { note_id })
+}
+
+/// Current owner of the tokenized note
+pub fn note_holder(deps: Deps, note_id: u64) -> Result<Addr, ContractError> {
+    let note_nft = CONFIG
+        .load(deps.storage)?
+        .note_nft
+        .ok_or(ContractError::NoNoteNft {})?;
+    let owner: OwnerOfResponse = deps.querier.query_wasm_smart(
+        note_nft,
+        &bond_note::msg::QueryMsg::OwnerOf {
+            token_id: note_id.to_string(),
+            include_expired: None,
+        },
+    )?;
+
+    Ok(Addr::unchecked(owner.owner))
+}
+
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+/// Page size when listing the notes held in the NFT contract
+const TOKENS_PAGE_LIMIT: u32 = 100;
+
+pub fn query_notes(
+    deps: Deps,
+    env: Env,
+    owner: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<NoteResponse>, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    NOTES
+        .prefix(&owner)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, note) = item?;
+            Ok::<_, ContractError>(NoteResponse {
+                id,
+                pending_payout: pending_payout(&note, &env)?,
+                note,
+            })
+        })
+        .collect()
+}
+
+pub fn referral_rewards(deps: Deps, referrer: String) -> Result<Uint128, ContractError> {
+    let referrer = deps.api.addr_validate(&referrer)?;
+    Ok(REFERRAL_REWARDS
+        .may_load(deps.storage, &referrer)?
         .unwrap_or_default())
 }
+
+pub fn is_allowed(
+    deps: Deps,
+    market_id: u64,
+    address: String,
+    proof: Vec<String>,
+) -> Result<bool, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    match load_market(deps.storage, market_id)?.allow_list {
+        Some(allow_list) => allow_list.is_allowed(&address, &proof),
+        None => Ok(true),
+    }
+}
+
+pub fn deposit_history(
+    deps: Deps,
+    address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> Result<Vec<HistoryResponse>, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    HISTORY
+        .prefix(&address)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            let (id, entry) = item?;
+            Ok(HistoryResponse { id, entry })
+        })
+        .collect()
+}
+
+pub fn market_stats(deps: Deps) -> Result<Vec<MarketStatsResponse>, ContractError> {
+    MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|market| {
+            let (market_id, market) = market?;
+            let average_price = if market.sold.is_zero() {
+                Decimal256::zero()
+            } else {
+                to_human(market.purchased, quote_decimals(deps, &market.quote_token)?)?
+                    / to_human(market.sold, OHM_DECIMALS)?
+            };
+            Ok(MarketStatsResponse {
+                market_id,
+                total_principle: market.purchased,
+                total_payout: market.sold,
+                average_price,
+            })
+        })
+        .collect()
+}
+
+pub fn auto_redeem(deps: Deps, address: String) -> Result<Option<AutoRedeem>, ContractError> {
+    let address = deps.api.addr_validate(&address)?;
+    Ok(AUTO_REDEEM.may_load(deps.storage, &address)?)
+}
+
+pub fn auto_redeemers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<AutoRedeemerResponse>, ContractError> {
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    AUTO_REDEEM
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| {
+            let (address, preference) = item?;
+            Ok(AutoRedeemerResponse {
+                address: address.to_string(),
+                preference,
+            })
+        })
+        .collect()
+}
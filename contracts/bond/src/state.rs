@@ -1,52 +1,318 @@
 // use `cw_storage_plus` to create ORM-like interface to storage
 // see: https://crates.io/crates/cw-storage-plus
 
+use std::cmp::{max, min};
+
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal256, Deps, DepsMut, Env, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Api, Decimal256, Deps, DepsMut, Env, Storage, Timestamp, Uint128};
+use cw_asset::AssetInfo;
 use cw_storage_plus::{Item, Map};
+use sha2::{Digest, Sha256};
 
 use crate::{query::debt_ratio, ContractError};
 
 pub const CONFIG: Item<Config> = Item::new("config");
-pub const TERMS: Item<Terms> = Item::new("terms");
-
-pub const TOTAL_DEBT: Item<Uint128> = Item::new("total_debt");
-pub const LAST_DECAY: Item<Timestamp> = Item::new("last_decay");
-pub const ADJUSTMENT: Item<Adjustment> = Item::new("adjustment");
+pub const NEXT_MARKET_ID: Item<u64> = Item::new("next_market_id");
+pub const MARKETS: Map<u64, Market> = Map::new("markets");
+pub const TERMS: Map<u64, Terms> = Map::new("market_terms");
+pub const ADJUSTMENTS: Map<u64, Adjustment> = Map::new("market_adjustments");
+/// Adjustments planned after the current one, in start order
+pub const ADJUSTMENT_SCHEDULES: Map<u64, Vec<AdjustmentStep>> =
+    Map::new("market_adjustment_schedules");
+/// Tuning of the markets created with a conclusion
+pub const TUNINGS: Map<u64, Tuning> = Map::new("market_tunings");
+/// Price of the markets sold in a sequential dutch auction
+pub const AUCTIONS: Map<u64, Auction> = Map::new("market_auctions");
+/// Decimals of the quote tokens set by the owner, they take precedence over the queried ones
+pub const DECIMALS: Map<&AssetInfo, u8> = Map::new("decimals");
 
-pub const BOND_INFO: Map<&Addr, Bond> = Map::new("bond_info");
+pub const NEXT_NOTE_ID: Item<u64> = Item::new("next_note_id");
+/// Notes of each owner, one per deposit
+pub const NOTES: Map<(&Addr, u64), Note> = Map::new("notes");
+/// Notes held as NFTs, they belong to the current owner of the token with the same id
+pub const TOKENIZED_NOTES: Map<u64, Note> = Map::new("tokenized_notes");
+pub const PAUSED: Item<PauseStatus> = Item::new("paused");
+pub const NEXT_HISTORY_ID: Item<u64> = Item::new("next_history_id");
+/// Deposits and redemptions of each address, the ids are shared by all addresses
+pub const HISTORY: Map<(&Addr, u64), HistoryEntry> = Map::new("history");
+/// Addresses whose notes keepers can redeem, with how they want to be paid
+pub const AUTO_REDEEM: Map<&Addr, AutoRedeem> = Map::new("auto_redeem");
+/// Quote tokens deposited by each address in the private markets
+pub const PURCHASED: Map<(u64, &Addr), Uint128> = Map::new("purchased");
+/// Referral rate of the whitelisted frontends
+pub const REFERRERS: Map<&Addr, Decimal256> = Map::new("referrers");
+/// OHM held by the depository for each referrer until it is claimed
+pub const REFERRAL_REWARDS: Map<&Addr, Uint128> = Map::new("referral_rewards");
 
 #[cw_serde]
 pub struct Config {
-    pub principle: String,
-    pub admin: Addr,
     pub staking: Addr,
     pub treasury: Addr,
+    pub guardian: Option<Addr>,
+    /// cw721 contract minting the tokenized notes
+    pub note_nft: Option<Addr>,
+    /// Share of each payout minted on top of it to the DAO
+    pub dao_fee: Decimal256,
+    pub dao_address: Option<Addr>,
+    /// Lock contract receiving the payouts of the markets with `lock_payout` terms
+    pub lock_contract: Option<Addr>,
+}
+
+#[cw_serde]
+pub struct OracleConfig {
+    pub source: OracleSource,
+    /// Refuse deposits when the bond is priced above the market
+    pub require_discount: bool,
+}
+
+impl OracleConfig {
+    pub fn validate(self, api: &dyn Api) -> Result<Self, ContractError> {
+        if let OracleSource::Contract { address } = &self.source {
+            api.addr_validate(address)?;
+        }
+        Ok(self)
+    }
+}
+
+/// Where the market price of OHM in quote tokens comes from
+#[cw_serde]
+pub enum OracleSource {
+    /// Our oracle contract, queried with the OHM denom and the market quote token
+    Contract { address: String },
+    /// The Injective oracle module, `oracle_type` is the module's `OracleType`.
+    /// `base` is the OHM symbol and `quote` the quote token symbol for this oracle type
+    Injective {
+        oracle_type: i32,
+        base: String,
+        quote: String,
+    },
+}
+
+/// Bonding calculator valuing the LP tokens deposited in a market
+#[cw_serde]
+pub struct CalculatorConfig {
+    pub calculator: String,
+    /// Pool of the LP token, passed to the calculator
+    pub pool: String,
+}
+
+impl CalculatorConfig {
+    pub fn validate(self, api: &dyn Api) -> Result<Self, ContractError> {
+        api.addr_validate(&self.calculator)?;
+        api.addr_validate(&self.pool)?;
+        Ok(self)
+    }
+}
+
+/// Addresses allowed to deposit in a private market, as a merkle tree
+#[cw_serde]
+pub struct AllowList {
+    /// Hex encoded root of the tree, its leaves are the sha256 hashes of the addresses.
+    /// Pairs are hashed in ascending order
+    pub merkle_root: String,
+    /// Max quote tokens deposited by each address
+    pub cap: Option<Uint128>,
+}
+
+impl AllowList {
+    pub fn validate(self) -> Result<Self, ContractError> {
+        decode_hash(&self.merkle_root).ok_or(ContractError::InvalidMerkleRoot {})?;
+        Ok(self)
+    }
+
+    /// Whether `proof` shows that `address` is a leaf of the tree
+    pub fn is_allowed(&self, address: &Addr, proof: &[String]) -> Result<bool, ContractError> {
+        let root = decode_hash(&self.merkle_root).ok_or(ContractError::InvalidMerkleRoot {})?;
+        let mut hash: [u8; 32] = Sha256::digest(address.as_bytes()).into();
+        for sibling in proof {
+            let sibling = decode_hash(sibling).ok_or(ContractError::InvalidMerkleProof {})?;
+            let mut pair = [hash, sibling];
+            pair.sort_unstable();
+            hash = Sha256::digest(pair.concat()).into();
+        }
+        Ok(hash == root)
+    }
+}
+
+fn decode_hash(hash: &str) -> Option<[u8; 32]> {
+    hex::decode(hash).ok()?.try_into().ok()
 }
 
 #[cw_serde]
 pub struct Market {
-    pub quote_token: Addr,
+    /// Native denom or cw20 token deposited in the market
+    pub quote_token: AssetInfo,
+    /// Whether `capacity` counts quote tokens deposited or OHM paid out
     pub capacity_in_quote: bool,
-    pub capacity: u128,
-    pub total_debt: u128,
-    pub max_payout: u128,
-    pub sold: u128,
-    pub purchased: u128,
+    /// Remaining capacity, the market is closed once it reaches zero
+    pub capacity: Uint128,
+    pub total_debt: Uint128,
+    pub last_decay: Timestamp,
+    /// OHM paid out by the market
+    pub sold: Uint128,
+    /// Quote tokens received by the market
+    pub purchased: Uint128,
+    pub oracle: Option<OracleConfig>,
+    /// Set for LP markets, the deposits are priced at their risk-free value
+    pub calculator: Option<CalculatorConfig>,
+    /// Set for private markets, only the listed addresses can deposit
+    pub allow_list: Option<AllowList>,
+}
+
+impl Market {
+    pub fn is_open(&self) -> bool {
+        !self.capacity.is_zero()
+    }
+
+    /// Denom or cw20 address of the quote token, used for oracle prices and note metadata
+    pub fn quote_id(&self) -> String {
+        match &self.quote_token {
+            AssetInfo::Native(denom) => denom.clone(),
+            AssetInfo::Cw20(addr) => addr.to_string(),
+            info => info.to_string(),
+        }
+    }
 }
 
 #[cw_serde]
 pub struct Terms {
+    /// Prices are in whole quote tokens per whole OHM, whatever the decimals of the tokens
     pub control_variable: Decimal256,
     pub minimum_price: Decimal256,
     pub max_payout: Decimal256,
     pub max_debt: Uint128,
-    pub vesting_term: u64,
+    pub vesting: VestingMode,
+    #[serde(default)]
+    pub pricing: PricingModel,
+    /// Payouts are locked linearly in the lock contract instead of vesting in notes
+    #[serde(default)]
+    pub lock_payout: bool,
+}
+
+impl Terms {
+    pub fn validate(&self, config: &Config) -> Result<(), ContractError> {
+        self.pricing.validate(self.minimum_price)?;
+        if self.vesting == VestingMode::FixedTerm(0) {
+            return Err(ContractError::InvalidVesting {});
+        }
+        if self.lock_payout && config.lock_contract.is_none() {
+            return Err(ContractError::NoLockContract {});
+        }
+        Ok(())
+    }
+
+    /// Vesting length of a note bought at `now`
+    pub fn vesting_time_left(&self, now: Timestamp) -> u64 {
+        match self.vesting {
+            VestingMode::FixedTerm(term) => term,
+            VestingMode::FixedExpiry(expiry) => expiry.seconds().saturating_sub(now.seconds()),
+        }
+    }
+}
+
+#[cw_serde]
+pub enum VestingMode {
+    /// Notes vest for this many seconds after the deposit
+    FixedTerm(u64),
+    /// All notes vest until this time, deposits are refused once it is reached
+    FixedExpiry(Timestamp),
 }
 
 #[cw_serde]
 #[derive(Default)]
-pub struct Bond {
+pub enum PricingModel {
+    /// The price is the control variable times the debt ratio, with a minimum price
+    #[default]
+    DebtRatio,
+    /// The price decays over time and jumps up after each purchase
+    SequentialDutchAuction(AuctionParams),
+}
+
+impl PricingModel {
+    pub fn validate(&self, minimum_price: Decimal256) -> Result<(), ContractError> {
+        if let PricingModel::SequentialDutchAuction(params) = self {
+            if params.deposit_interval == 0
+                || params.decay_speed > Decimal256::one()
+                || minimum_price > params.initial_price
+            {
+                return Err(ContractError::InvalidPricing {});
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cw_serde]
+/// The price never decays below the minimum price of the terms
+pub struct AuctionParams {
+    pub initial_price: Decimal256,
+    /// Fraction of the price lost over a deposit interval without deposits
+    pub decay_speed: Decimal256,
+    /// Max size deposits made at this interval keep the price stable
+    pub deposit_interval: u64,
+}
+
+impl AuctionParams {
+    /// Price of the auction at `now`, it decays linearly since the last deposit
+    pub fn decayed_price(
+        &self,
+        auction: &Auction,
+        minimum_price: Decimal256,
+        now: Timestamp,
+    ) -> Decimal256 {
+        let elapsed = now.seconds().saturating_sub(auction.last_update.seconds());
+        let decay = self.decay_speed * Decimal256::from_ratio(elapsed, self.deposit_interval);
+        let price = auction.price * (Decimal256::one() - min(decay, Decimal256::one()));
+        max(price, minimum_price)
+    }
+
+    /// Price after a deposit paying `payout`, a max size deposit makes up for one interval of decay
+    pub fn bumped_price(
+        &self,
+        price: Decimal256,
+        payout: Uint128,
+        max_payout: Uint128,
+    ) -> Decimal256 {
+        price * (Decimal256::one() + self.decay_speed * Decimal256::from_ratio(payout, max_payout))
+    }
+}
+
+#[cw_serde]
+pub struct Auction {
+    /// Price right after the last deposit
+    pub price: Decimal256,
+    pub last_update: Timestamp,
+}
+
+/// Starts the auction of the market if its terms sell it in a sequential dutch auction.
+/// An auction already running goes on, its price is kept above the minimum price of the terms
+pub fn init_auction(
+    storage: &mut dyn Storage,
+    market_id: u64,
+    terms: &Terms,
+    now: Timestamp,
+) -> Result<(), ContractError> {
+    if let PricingModel::SequentialDutchAuction(params) = &terms.pricing {
+        let auction = match AUCTIONS.may_load(storage, market_id)? {
+            Some(auction) => Auction {
+                price: max(auction.price, terms.minimum_price),
+                ..auction
+            },
+            None => Auction {
+                price: params.initial_price,
+                last_update: now,
+            },
+        };
+        AUCTIONS.save(storage, market_id, &auction)?;
+    }
+    Ok(())
+}
+
+/// Payout of a single deposit, it vests linearly from its own start
+#[cw_serde]
+pub struct Note {
+    pub market_id: u64,
+    /// OHM left to redeem
     pub payout: Uint128,
     pub vesting_time_left: u64,
     pub last_time: Timestamp,
@@ -61,24 +327,189 @@ pub struct Adjustment {
     pub last_time: Timestamp,
 }
 
-pub fn bond_price(deps: DepsMut, env: Env) -> Result<Decimal256, ContractError> {
-    let mut terms = TERMS.load(deps.storage)?;
+/// Step of an adjustment schedule, it becomes the adjustment of the market once the previous
+/// one has reached its target and `start` has passed
+#[cw_serde]
+pub struct AdjustmentStep {
+    pub start: Timestamp,
+    pub add: bool,
+    pub rate: Decimal256,
+    pub target: Decimal256,
+    pub buffer: u64,
+}
+
+impl From<AdjustmentStep> for Adjustment {
+    fn from(step: AdjustmentStep) -> Self {
+        Adjustment {
+            add: step.add,
+            rate: step.rate,
+            target: step.target,
+            buffer: step.buffer,
+            last_time: step.start,
+        }
+    }
+}
+
+/// Sells the capacity of a market evenly until its conclusion
+#[cw_serde]
+pub struct TuningConfig {
+    /// Deposits are refused from this time
+    pub conclusion: Timestamp,
+    /// Expected time between two max size deposits
+    pub deposit_interval: u64,
+    /// Minimum time between two tunes of the control variable
+    pub tune_interval: u64,
+}
+
+impl TuningConfig {
+    pub fn validate(self, env: &Env) -> Result<Self, ContractError> {
+        if self.conclusion <= env.block.time
+            || self.deposit_interval == 0
+            || self.tune_interval < self.deposit_interval
+        {
+            return Err(ContractError::InvalidTuning {});
+        }
+        Ok(self)
+    }
+}
+
+#[cw_serde]
+pub struct Tuning {
+    pub config: TuningConfig,
+    pub last_tune: Timestamp,
+}
+
+impl Tuning {
+    pub fn next_tune(&self) -> Timestamp {
+        self.last_tune.plus_seconds(self.config.tune_interval)
+    }
+}
+
+#[cw_serde]
+pub struct AutoRedeem {
+    /// Stake the payouts instead of sending them
+    pub stake: bool,
+    /// Share of each payout paid to the keeper redeeming it
+    pub tip: Decimal256,
+}
+
+#[cw_serde]
+pub struct HistoryEntry {
+    pub event: HistoryEvent,
+    pub timestamp: Timestamp,
+}
+
+#[cw_serde]
+pub enum HistoryEvent {
+    /// `amount` quote tokens bought `payout` OHM at `price` quote tokens per OHM
+    /// `note_id` is `None` when the payout was locked in the lock contract
+    Deposit {
+        market_id: u64,
+        note_id: Option<u64>,
+        amount: Uint128,
+        price: Decimal256,
+        payout: Uint128,
+    },
+    /// OHM paid out of the vested notes
+    Redeem { payout: Uint128, stake: bool },
+}
+
+/// Adds an entry to the history of `address`
+pub fn record_history(
+    storage: &mut dyn Storage,
+    address: &Addr,
+    event: HistoryEvent,
+    timestamp: Timestamp,
+) -> Result<(), ContractError> {
+    let id = NEXT_HISTORY_ID.may_load(storage)?.unwrap_or_default();
+    NEXT_HISTORY_ID.save(storage, &(id + 1))?;
+    HISTORY.save(storage, (address, id), &HistoryEntry { event, timestamp })?;
+    Ok(())
+}
+
+/// Operations the guardian can pause
+#[cw_serde]
+pub enum Operation {
+    Deposit,
+    Redeem,
+}
 
-    let mut price = terms.control_variable * debt_ratio(deps.as_ref(), env)?;
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseStatus {
+    pub deposit: bool,
+    pub redeem: bool,
+}
+
+impl PauseStatus {
+    pub fn is_paused(&self, operation: &Operation) -> bool {
+        match operation {
+            Operation::Deposit => self.deposit,
+            Operation::Redeem => self.redeem,
+        }
+    }
+
+    pub fn set(&mut self, operation: &Operation, paused: bool) {
+        match operation {
+            Operation::Deposit => self.deposit = paused,
+            Operation::Redeem => self.redeem = paused,
+        }
+    }
+}
+
+pub fn pause_status(storage: &dyn Storage) -> Result<PauseStatus, ContractError> {
+    Ok(PAUSED.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn set_paused(
+    storage: &mut dyn Storage,
+    operations: &[Operation],
+    paused: bool,
+) -> Result<(), ContractError> {
+    let mut status = pause_status(storage)?;
+    operations.iter().for_each(|op| status.set(op, paused));
+    PAUSED.save(storage, &status)?;
+    Ok(())
+}
+
+pub fn assert_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    if pause_status(storage)?.is_paused(&operation) {
+        return Err(ContractError::Paused { operation });
+    }
+    Ok(())
+}
+
+pub fn load_market(storage: &dyn Storage, market_id: u64) -> Result<Market, ContractError> {
+    MARKETS
+        .may_load(storage, market_id)?
+        .ok_or(ContractError::MarketNotFound { market_id })
+}
+
+pub fn bond_price(deps: DepsMut, env: Env, market_id: u64) -> Result<Decimal256, ContractError> {
+    let mut terms = TERMS.load(deps.storage, market_id)?;
+    if let PricingModel::SequentialDutchAuction(_) = terms.pricing {
+        return query_bond_price(deps.as_ref(), env, market_id);
+    }
+
+    let mut price = terms.control_variable * debt_ratio(deps.as_ref(), env, market_id)?;
     if price < terms.minimum_price {
         price = terms.minimum_price;
     } else if !terms.minimum_price.is_zero() {
         terms.minimum_price = Decimal256::zero();
     };
 
-    TERMS.save(deps.storage, &terms)?;
+    TERMS.save(deps.storage, market_id, &terms)?;
 
     Ok(price)
 }
-pub fn query_bond_price(deps: Deps, env: Env) -> Result<Decimal256, ContractError> {
-    let terms = TERMS.load(deps.storage)?;
+pub fn query_bond_price(deps: Deps, env: Env, market_id: u64) -> Result<Decimal256, ContractError> {
+    let terms = TERMS.load(deps.storage, market_id)?;
+    if let PricingModel::SequentialDutchAuction(params) = &terms.pricing {
+        let auction = AUCTIONS.load(deps.storage, market_id)?;
+        return Ok(params.decayed_price(&auction, terms.minimum_price, env.block.time));
+    }
 
-    let mut price = terms.control_variable * debt_ratio(deps, env)?;
+    let mut price = terms.control_variable * debt_ratio(deps, env, market_id)?;
     if price < terms.minimum_price {
         price = terms.minimum_price;
     }
@@ -0,0 +1,211 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Binary, Decimal256, Deps, DepsMut, Empty, Env, MessageInfo, Response, StdError,
+    StdResult, Uint128, Uint256,
+};
+use msgs::{assert_can_migrate, migrate_attributes};
+use staking_contract::state::OHM_DECIMALS;
+
+use crate::msg::{InstantiateMsg, MigrateMsg, PoolQueryMsg, PoolResponse, QueryMsg};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(Response::new())
+}
+
+/// The calculator is stateless, it only answers queries
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(_deps: DepsMut, _env: Env, _info: MessageInfo, _msg: Empty) -> StdResult<Response> {
+    Err(StdError::generic_err(
+        "The bonding calculator has no execute messages",
+    ))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::KValue { pool } => to_json_binary(&k_value(&query_pool(deps, pool)?)?),
+        QueryMsg::TotalValue { pool } => to_json_binary(&total_value(&query_pool(deps, pool)?)?),
+        QueryMsg::Valuation { pool, amount } => {
+            to_json_binary(&valuation(&query_pool(deps, pool)?, amount)?)
+        }
+        QueryMsg::Markdown { pool, ohm } => {
+            to_json_binary(&markdown(&query_pool(deps, pool)?, &ohm)?)
+        }
+    }
+}
+
+fn query_pool(deps: Deps, pool: String) -> StdResult<PoolResponse> {
+    let pool = deps.api.addr_validate(&pool)?;
+    deps.querier.query_wasm_smart(pool, &PoolQueryMsg::Pool {})
+}
+
+/// Reserves of the pool converted to OHM precision, so that assets with different decimals
+/// weigh the same in `k`
+fn normalized_reserves(pool: &PoolResponse) -> StdResult<[Uint256; 2]> {
+    let normalize = |reserve: Uint128, decimals: u8| -> StdResult<Uint256> {
+        let reserve = Uint256::from(reserve);
+        if decimals >= OHM_DECIMALS {
+            Ok(reserve / Uint256::from(10u128).checked_pow((decimals - OHM_DECIMALS).into())?)
+        } else {
+            Ok(reserve.checked_mul(
+                Uint256::from(10u128).checked_pow((OHM_DECIMALS - decimals).into())?,
+            )?)
+        }
+    };
+    Ok([
+        normalize(pool.reserves[0], pool.decimals[0])?,
+        normalize(pool.reserves[1], pool.decimals[1])?,
+    ])
+}
+
+pub fn k_value(pool: &PoolResponse) -> StdResult<Uint256> {
+    let [reserve_0, reserve_1] = normalized_reserves(pool)?;
+    Ok(reserve_0.checked_mul(reserve_1)?)
+}
+
+pub fn total_value(pool: &PoolResponse) -> StdResult<Uint128> {
+    let k = Decimal256::from_atomics(k_value(pool)?, 0)
+        .map_err(|_| StdError::generic_err("The pool reserves are too large"))?;
+    Ok((Uint256::from(2u128) * k.sqrt()).try_into()?)
+}
+
+/// Share of the total value held by `amount` LP tokens
+pub fn valuation(pool: &PoolResponse, amount: Uint128) -> StdResult<Uint128> {
+    if pool.total_share.is_zero() {
+        return Err(StdError::generic_err("The pool has no liquidity"));
+    }
+    Ok(total_value(pool)?.multiply_ratio(amount, pool.total_share))
+}
+
+/// `2 * ohm_reserve / total_value`, below one when the pool holds less OHM than its risk-free value
+pub fn markdown(pool: &PoolResponse, ohm: &str) -> StdResult<Decimal256> {
+    let ohm_index = pool
+        .assets
+        .iter()
+        .position(|asset| asset == ohm)
+        .ok_or_else(|| StdError::generic_err(format!("{ohm} is not an asset of the pool")))?;
+    let total_value = total_value(pool)?;
+    if total_value.is_zero() {
+        return Err(StdError::generic_err("The pool has no liquidity"));
+    }
+    let ohm_reserve = normalized_reserves(pool)?[ohm_index];
+
+    Decimal256::checked_from_ratio(ohm_reserve.checked_mul(Uint256::from(2u128))?, total_value)
+        .map_err(|_| StdError::generic_err("The markdown is too large"))
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
+    let from_version = assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION)))
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::str::FromStr;
+
+    use cosmwasm_std::{Decimal256, Uint128, Uint256};
+
+    use crate::contract::{k_value, markdown, total_value, valuation};
+    use crate::msg::PoolResponse;
+
+    const OHM: &str = "ohm";
+    const INJ: &str = "inj";
+
+    /// 1_000 OHM (6 decimals) against 4_000 INJ (18 decimals)
+    fn pool(total_share: u128) -> PoolResponse {
+        PoolResponse {
+            assets: [OHM.to_string(), INJ.to_string()],
+            decimals: [6, 18],
+            reserves: [
+                Uint128::new(1_000_000_000),
+                Uint128::new(4_000_000_000_000_000_000_000),
+            ],
+            total_share: Uint128::new(total_share),
+        }
+    }
+
+    #[test]
+    pub fn reserves_are_normalized() -> anyhow::Result<()> {
+        let pool = pool(1_000);
+
+        // Both reserves count in OHM precision: 1_000e6 * 4_000e6
+        assert_eq!(
+            k_value(&pool)?,
+            Uint256::from(4_000_000_000_000_000_000u128)
+        );
+        // 2 * sqrt(k) = 4_000 OHM
+        assert_eq!(total_value(&pool)?, Uint128::new(4_000_000_000));
+
+        // The same pool with the assets swapped is worth the same
+        let swapped = PoolResponse {
+            assets: [INJ.to_string(), OHM.to_string()],
+            decimals: [18, 6],
+            reserves: [pool.reserves[1], pool.reserves[0]],
+            total_share: pool.total_share,
+        };
+        assert_eq!(total_value(&swapped)?, total_value(&pool)?);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn valuation_with_mismatched_decimals() -> anyhow::Result<()> {
+        let pool = pool(1_000);
+
+        assert_eq!(
+            valuation(&pool, Uint128::new(1_000))?,
+            Uint128::new(4_000_000_000)
+        );
+        assert_eq!(
+            valuation(&pool, Uint128::new(250))?,
+            Uint128::new(1_000_000_000)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn markdown_with_mismatched_decimals() -> anyhow::Result<()> {
+        let pool = pool(1_000);
+
+        // 2 * 1_000 OHM / 4_000 OHM of value
+        assert_eq!(markdown(&pool, OHM)?, Decimal256::from_str("0.5")?);
+        assert_eq!(markdown(&pool, INJ)?, Decimal256::from_str("2")?);
+        markdown(&pool, "usdt").unwrap_err();
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn zero_lp_supply() -> anyhow::Result<()> {
+        let pool = pool(0);
+
+        let err = valuation(&pool, Uint128::new(1_000)).unwrap_err();
+        assert!(err.to_string().contains("The pool has no liquidity"));
+        // The markdown only depends on the reserves
+        assert_eq!(markdown(&pool, OHM)?, Decimal256::from_str("0.5")?);
+
+        let empty = PoolResponse {
+            reserves: [Uint128::zero(), Uint128::zero()],
+            ..pool
+        };
+        let err = markdown(&empty, OHM).unwrap_err();
+        assert!(err.to_string().contains("The pool has no liquidity"));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,26 @@
+use crate::msg::{InstantiateMsg, MigrateMsg, QueryMsg};
+use cosmwasm_std::Empty;
+use cw_orch::{interface, prelude::*};
+
+#[interface(InstantiateMsg, Empty, QueryMsg, MigrateMsg)]
+pub struct BondingCalculator;
+
+impl<Chain: CwEnv> Uploadable for BondingCalculator<Chain> {
+    /// Return the path to the wasm file corresponding to the contract
+    fn wasm(&self) -> WasmPath {
+        artifacts_dir_from_workspace!()
+            .find_wasm_path("bonding_calculator")
+            .unwrap()
+    }
+    /// Returns a CosmWasm contract wrapper
+    fn wrapper(&self) -> Box<dyn MockContract<Empty>> {
+        Box::new(
+            ContractWrapper::new_with_empty(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_migrate(crate::contract::migrate),
+        )
+    }
+}
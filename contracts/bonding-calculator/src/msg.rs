@@ -0,0 +1,51 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal256, Uint128, Uint256};
+
+/// Message type for `instantiate` entry_point
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+/// Message type for `query` entry_point.
+/// `pool` is the address of a contract answering [`PoolQueryMsg`]
+#[cw_serde]
+#[derive(QueryResponses)]
+#[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
+pub enum QueryMsg {
+    /// Product of the two reserves of the pool, both in OHM precision
+    #[returns(Uint256)]
+    KValue { pool: String },
+    /// Risk-free value of all the liquidity of the pool, `2 * sqrt(k)`, in OHM precision
+    #[returns(Uint128)]
+    TotalValue { pool: String },
+    /// Risk-free value of `amount` LP tokens of the pool, in OHM precision
+    #[returns(Uint128)]
+    Valuation { pool: String, amount: Uint128 },
+    /// Ratio between the `ohm` reserve and half the total value of the pool,
+    /// used to mark down the price of the LP token
+    #[returns(Decimal256)]
+    Markdown { pool: String, ohm: String },
+}
+
+/// Queries a pool has to answer to be valued by the calculator
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum PoolQueryMsg {
+    #[returns(PoolResponse)]
+    Pool {},
+}
+
+#[cw_serde]
+pub struct PoolResponse {
+    /// Denoms or addresses of the two assets of the pool
+    pub assets: [String; 2],
+    /// Decimals of the two assets of the pool
+    pub decimals: [u8; 2],
+    /// Reserves of the two assets of the pool, in the smallest unit of each asset
+    pub reserves: [Uint128; 2],
+    /// Supply of LP tokens
+    pub total_share: Uint128,
+}
+
+/// Message type for `migrate` entry_point
+#[cw_serde]
+pub struct MigrateMsg {}
@@ -0,0 +1,767 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response, Uint128,
+};
+use cw_asset::{Asset, AssetInfo, AssetInfoUnchecked};
+use msgs::{assert_can_migrate, migrate_attributes};
+
+use bond::query::quote_decimals;
+use bond::state::{Adjustment, OracleConfig, PricingModel, Terms};
+
+use crate::error::{ContractError, ContractResult, QueryResult};
+use crate::execute::{deposit, deposit_one_coin, redeem, sweep_msgs};
+use crate::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
+use crate::query::{
+    inverse_bond_quote, query_adjustment, query_config, query_market, query_markets, query_notes,
+};
+use crate::state::{
+    load_market, pause_status, set_paused, Config, InverseMarket, Operation, ADJUSTMENTS, CONFIG,
+    DECIMALS, MARKETS, NEXT_MARKET_ID, TERMS,
+};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Handling contract instantiation
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner = msg.owner.unwrap_or_else(|| info.sender.to_string());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            staking: deps.api.addr_validate(&msg.staking)?,
+            treasury: deps.api.addr_validate(&msg.treasury)?,
+            guardian: msg
+                .guardian
+                .map(|guardian| deps.api.addr_validate(&guardian))
+                .transpose()?,
+        },
+    )?;
+    NEXT_MARKET_ID.save(deps.storage, &0)?;
+
+    Ok(Response::new())
+}
+
+/// Handling contract execution
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Deposit {
+            market_id,
+            min_price,
+            depositor,
+        } => deposit(deps, env, info, market_id, min_price, depositor),
+        ExecuteMsg::Redeem { recipient } => redeem(deps, env, recipient),
+        ExecuteMsg::CreateMarket {
+            reserve,
+            capacity,
+            terms,
+            oracle,
+        } => create_market(deps, env, info, reserve, capacity, terms, oracle),
+        ExecuteMsg::CloseMarket { market_id } => close_market(deps, info, market_id),
+        ExecuteMsg::UpdateTerms { market_id, terms } => update_terms(deps, info, market_id, terms),
+        ExecuteMsg::UpdateConfig {
+            staking,
+            treasury,
+            guardian,
+        } => update_config(deps, info, staking, treasury, guardian),
+        ExecuteMsg::UpdateAdjustment {
+            market_id,
+            add,
+            rate,
+            target,
+            buffer,
+        } => update_adjustment(deps, info, market_id, add, rate, target, buffer),
+        ExecuteMsg::SetDecimals { asset, decimals } => set_decimals(deps, info, asset, decimals),
+        ExecuteMsg::RemoveGuardian {} => remove_guardian(deps, info),
+        ExecuteMsg::Pause { operations } => pause(deps, info, operations),
+        ExecuteMsg::Unpause { operations } => unpause(deps, info, operations),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
+    }
+}
+
+/// Handling contract query
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> QueryResult {
+    match msg {
+        QueryMsg::Config {} => Ok(to_json_binary(&query_config(deps)?)?),
+        QueryMsg::Markets {} => Ok(to_json_binary(&query_markets(deps)?)?),
+        QueryMsg::Market { id } => Ok(to_json_binary(&query_market(deps, id)?)?),
+        QueryMsg::Adjustment { market_id } => {
+            Ok(to_json_binary(&query_adjustment(deps, market_id)?)?)
+        }
+        QueryMsg::InverseBondQuote { market_id, amount } => Ok(to_json_binary(
+            &inverse_bond_quote(deps, market_id, amount)?,
+        )?),
+        QueryMsg::Notes { owner } => Ok(to_json_binary(&query_notes(deps, env, owner)?)?),
+        QueryMsg::Decimals { asset } => Ok(to_json_binary(&quote_decimals(
+            deps,
+            &asset.check(deps.api, None)?,
+        )?)?),
+        QueryMsg::PauseStatus {} => Ok(to_json_binary(&pause_status(deps.storage)?)?),
+        QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
+    }
+}
+
+/// Handling governance calls, they bypass the owner and guardian checks
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> ContractResult {
+    match msg {
+        SudoMsg::UpdateOwner { owner } => {
+            let ownership = cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+            Ok(Response::new()
+                .add_attribute("action", "sudo_update_owner")
+                .add_attributes(ownership.into_attributes()))
+        }
+        SudoMsg::Pause { operations } => {
+            set_paused(deps.storage, &operations, true)?;
+            Ok(Response::new().add_attribute("action", "sudo_pause"))
+        }
+        SudoMsg::Unpause { operations } => {
+            set_paused(deps.storage, &operations, false)?;
+            Ok(Response::new().add_attribute("action", "sudo_unpause"))
+        }
+        SudoMsg::SweepFunds { recipient } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            Ok(Response::new()
+                .add_messages(sweep_msgs(deps.as_ref(), &env, &recipient)?)
+                .add_attribute("action", "sudo_sweep_funds")
+                .add_attribute("recipient", recipient))
+        }
+    }
+}
+
+/// Handling contract migration
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> ContractResult {
+    let from_version = assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION)))
+}
+
+/// Inverse bonds are priced by their oracle, the auction pricing of the depository doesn't apply
+fn validate_terms(terms: &Terms) -> Result<(), ContractError> {
+    if terms.pricing != PricingModel::DebtRatio {
+        return Err(ContractError::InvalidPricing {});
+    }
+    Ok(())
+}
+
+pub fn create_market(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    reserve: AssetInfoUnchecked,
+    capacity: Uint128,
+    terms: Terms,
+    oracle: OracleConfig,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    validate_terms(&terms)?;
+    let reserve = reserve.check(deps.api, None)?;
+    // Payouts can't be computed without the reserve decimals
+    quote_decimals(deps.as_ref(), &reserve)?;
+
+    // The reserves are held by the market until they are paid out
+    let transfer_msg = match &reserve {
+        AssetInfo::Native(denom) => {
+            let deposited = deposit_one_coin(info, denom.clone())?;
+            if deposited != capacity {
+                return Err(ContractError::NotEnoughDeposited {
+                    expected: capacity,
+                    got: deposited,
+                });
+            }
+            None
+        }
+        _ => Some(
+            Asset::new(reserve.clone(), capacity)
+                .transfer_from_msg(info.sender, env.contract.address.clone())?,
+        ),
+    };
+
+    let market_id = NEXT_MARKET_ID.may_load(deps.storage)?.unwrap_or_default();
+    NEXT_MARKET_ID.save(deps.storage, &(market_id + 1))?;
+
+    let market = InverseMarket {
+        reserve,
+        capacity,
+        total_debt: Uint128::zero(),
+        last_decay: env.block.time,
+        burnt: Uint128::zero(),
+        paid: Uint128::zero(),
+        oracle: oracle.validate(deps.api)?,
+    };
+    MARKETS.save(deps.storage, market_id, &market)?;
+    TERMS.save(deps.storage, market_id, &terms)?;
+    ADJUSTMENTS.save(
+        deps.storage,
+        market_id,
+        &Adjustment {
+            add: true,
+            rate: Decimal256::zero(),
+            target: Decimal256::zero(),
+            buffer: 0,
+            last_time: env.block.time,
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_messages(transfer_msg)
+        .add_attribute("action", "create_inverse_market")
+        .add_attribute("market_id", market_id.to_string())
+        .add_attribute("reserve", market.reserve.to_string()))
+}
+
+pub fn close_market(deps: DepsMut, info: MessageInfo, market_id: u64) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let config = CONFIG.load(deps.storage)?;
+    let mut market = load_market(deps.storage, market_id)?;
+    let refund = Asset::new(market.reserve.clone(), market.capacity);
+    market.capacity = Uint128::zero();
+    MARKETS.save(deps.storage, market_id, &market)?;
+
+    let refund_msg = if refund.amount.is_zero() {
+        None
+    } else {
+        Some(refund.transfer_msg(&config.treasury)?)
+    };
+
+    Ok(Response::new()
+        .add_messages(refund_msg)
+        .add_attribute("action", "close_inverse_market")
+        .add_attribute("market_id", market_id.to_string()))
+}
+
+pub fn update_terms(
+    deps: DepsMut,
+    info: MessageInfo,
+    market_id: u64,
+    terms: Terms,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    load_market(deps.storage, market_id)?;
+    validate_terms(&terms)?;
+    TERMS.save(deps.storage, market_id, &terms)?;
+    Ok(Response::new())
+}
+
+pub fn update_config(
+    deps: DepsMut,
+    info: MessageInfo,
+    staking: Option<String>,
+    treasury: Option<String>,
+    guardian: Option<String>,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(staking) = staking {
+        config.staking = deps.api.addr_validate(&staking)?;
+    }
+    if let Some(treasury) = treasury {
+        config.treasury = deps.api.addr_validate(&treasury)?;
+    }
+    if let Some(guardian) = guardian {
+        config.guardian = Some(deps.api.addr_validate(&guardian)?);
+    }
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new())
+}
+
+pub fn update_adjustment(
+    deps: DepsMut,
+    info: MessageInfo,
+    market_id: u64,
+    add: Option<bool>,
+    rate: Option<Decimal256>,
+    target: Option<Decimal256>,
+    buffer: Option<u64>,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    load_market(deps.storage, market_id)?;
+    let mut adjustment = ADJUSTMENTS.load(deps.storage, market_id)?;
+
+    if let Some(add) = add {
+        adjustment.add = add;
+    }
+    if let Some(rate) = rate {
+        adjustment.rate = rate;
+    }
+    if let Some(target) = target {
+        adjustment.target = target;
+    }
+    if let Some(buffer) = buffer {
+        adjustment.buffer = buffer;
+    }
+
+    ADJUSTMENTS.save(deps.storage, market_id, &adjustment)?;
+
+    Ok(Response::new())
+}
+
+pub fn set_decimals(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfoUnchecked,
+    decimals: Option<u8>,
+) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let asset = asset.check(deps.api, None)?;
+
+    match decimals {
+        Some(decimals) => {
+            if u32::from(decimals) > Decimal256::DECIMAL_PLACES {
+                return Err(bond::ContractError::InvalidDecimals {}.into());
+            }
+            DECIMALS.save(deps.storage, &asset, &decimals)?;
+        }
+        None => DECIMALS.remove(deps.storage, &asset),
+    }
+
+    Ok(Response::new()
+        .add_attribute("action", "set_decimals")
+        .add_attribute("asset", asset.to_string())
+        .add_attribute(
+            "decimals",
+            decimals.map(|d| d.to_string()).unwrap_or_default(),
+        ))
+}
+
+pub fn remove_guardian(deps: DepsMut, info: MessageInfo) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.guardian = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "remove_guardian"))
+}
+
+pub fn pause(deps: DepsMut, info: MessageInfo, operations: Vec<Operation>) -> ContractResult {
+    let config = CONFIG.load(deps.storage)?;
+    if !cw_ownable::is_owner(deps.storage, &info.sender)?
+        && Some(&info.sender) != config.guardian.as_ref()
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    set_paused(deps.storage, &operations, true)?;
+
+    Ok(Response::new().add_attribute("action", "pause"))
+}
+
+pub fn unpause(deps: DepsMut, info: MessageInfo, operations: Vec<Operation>) -> ContractResult {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    set_paused(deps.storage, &operations, false)?;
+
+    Ok(Response::new().add_attribute("action", "unpause"))
+}
+
+#[cfg(test)]
+pub mod test {
+    use std::str::FromStr;
+
+    use bond::state::{OracleConfig, OracleSource, PricingModel, Terms, VestingMode};
+    use cosmwasm_std::{coin, coins, Decimal256, Uint128};
+    use cw_asset::AssetInfoUnchecked;
+    use cw_orch::injective_test_tube::injective_test_tube::Account;
+    use cw_orch::{injective_test_tube::InjectiveTestTube, prelude::*};
+    use cw_plus_interface::cw1_whitelist::Cw1Whitelist;
+    use inverse_bond::interface::InverseBond;
+    use inverse_bond::msg::{
+        ExecuteMsgFns as _, InverseBondQuoteResponse, QueryMsgFns as _, SudoMsg,
+    };
+    use inverse_bond::query::INSTANT_DECAY_LENGTH;
+    use inverse_bond::state::{Operation, PauseStatus};
+    use oracle::interface::Oracle;
+    use staking_contract::msg::QueryMsgFns as _;
+    use tests::deploy::upload::{Shogun, ShogunDeployment};
+    use tests::sudo::wasm_sudo;
+    use tests::tokenfactory::assert_balance;
+
+    pub const AMOUNT_TO_CREATE_DENOM: u128 = 10_000_000_000_000_000_000u128;
+    pub const FUNDS_MULTIPLIER: u128 = 100_000;
+    pub const RESERVE_TOKEN: &str = "ureserve";
+    pub const RESERVES: u128 = 100_000;
+
+    pub fn terms(vesting: u64) -> anyhow::Result<Terms> {
+        Ok(Terms {
+            control_variable: Decimal256::from_str("0.05")?, // 5% premium
+            minimum_price: Decimal256::zero(),
+            max_payout: Decimal256::from_str("0.1")?,
+            max_debt: 500_000u128.into(),
+            vesting: VestingMode::FixedTerm(vesting),
+            pricing: PricingModel::DebtRatio,
+            lock_payout: false,
+        })
+    }
+
+    pub fn init() -> anyhow::Result<(Shogun<InjectiveTestTube>, InverseBond<InjectiveTestTube>)> {
+        init_with_decimals(6)
+    }
+
+    pub fn init_with_decimals(
+        reserve_decimals: u8,
+    ) -> anyhow::Result<(Shogun<InjectiveTestTube>, InverseBond<InjectiveTestTube>)> {
+        let mut chain = InjectiveTestTube::new(vec![
+            coin(AMOUNT_TO_CREATE_DENOM * FUNDS_MULTIPLIER, "inj"),
+            coin(10_000_000, RESERVE_TOKEN),
+        ]);
+        let block_info = chain.block_info()?;
+        let treasury = chain.init_account(vec![])?;
+
+        let cw1 = Cw1Whitelist::new("cw1-whitelist", chain.clone());
+        cw1.upload()?;
+
+        let shogun = Shogun::deploy_on(
+            chain.clone(),
+            ShogunDeployment {
+                epoch_length: 100,
+                first_epoch_time: block_info.time.seconds() + 1,
+                epoch_apr: Decimal256::from_str("0.1")?,
+                initial_balances: vec![(chain.sender().to_string(), 1_000_000u128)],
+                amount_to_create_denom: AMOUNT_TO_CREATE_DENOM,
+                fee_token: "inj".to_string(),
+                staking_symbol: "sSHGN".to_string(),
+                staking_name: "sSHOGUN".to_string(),
+                warmup_length: 50,
+                cw1_code_id: cw1.code_id()?,
+                treasury: treasury.address(),
+                note_name: "Shogun bond notes".to_string(),
+                note_symbol: "SBOND".to_string(),
+            },
+        )?;
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+
+        // OHM trades at 2 reserve tokens
+        let oracle = Oracle::new("oracle", chain.clone());
+        oracle.upload()?;
+        oracle.instantiate(
+            &oracle::msg::InstantiateMsg {
+                owner: chain.sender().to_string(),
+                base_asset: RESERVE_TOKEN.to_string(),
+                guardian: None,
+            },
+            None,
+            None,
+        )?;
+        oracle.execute(
+            &oracle::msg::ExecuteMsg::RegisterFeeder {
+                asset: ohm_denom.clone(),
+                feeder: chain.sender().to_string(),
+            },
+            None,
+        )?;
+        oracle.execute(
+            &oracle::msg::ExecuteMsg::FeedPrice {
+                prices: vec![(ohm_denom, Decimal256::from_str("2")?)],
+            },
+            None,
+        )?;
+
+        let inverse_bond = InverseBond::new("shogun:inverse-bond", chain.clone());
+        inverse_bond.upload()?;
+        inverse_bond.instantiate(
+            &inverse_bond::msg::InstantiateMsg {
+                owner: None,
+                staking: shogun.staking.address()?.to_string(),
+                treasury: treasury.address(),
+                guardian: None,
+            },
+            None,
+            None,
+        )?;
+
+        let oracle = OracleConfig {
+            source: OracleSource::Contract {
+                address: oracle.address()?.to_string(),
+            },
+            require_discount: false,
+        };
+        inverse_bond.set_decimals(
+            AssetInfoUnchecked::native(RESERVE_TOKEN),
+            Some(reserve_decimals),
+        )?;
+        for vesting in [0, 3600] {
+            inverse_bond.create_market(
+                RESERVES.into(),
+                oracle.clone(),
+                AssetInfoUnchecked::native(RESERVE_TOKEN),
+                terms(vesting)?,
+                &coins(RESERVES, RESERVE_TOKEN),
+            )?;
+        }
+
+        Ok((shogun, inverse_bond))
+    }
+
+    #[test]
+    pub fn inverse_bonds_burn_ohm_for_reserves() -> anyhow::Result<()> {
+        let (shogun, inverse_bond) = init()?;
+        let chain = inverse_bond.get_chain().clone();
+        let sender = chain.sender().to_string();
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+
+        assert_eq!(
+            inverse_bond.inverse_bond_quote(10_000u128.into(), 0)?,
+            InverseBondQuoteResponse {
+                price: Decimal256::from_str("2.1")?,
+                payout: 21_000u128.into(),
+            }
+        );
+
+        // The first market pays out instantly
+        inverse_bond.deposit(
+            sender.clone(),
+            0,
+            Decimal256::from_str("2.1")?,
+            &coins(10_000, ohm_denom.clone()),
+        )?;
+        assert_balance(chain.clone(), ohm_denom.clone(), 990_000, sender.clone())?;
+        assert_balance(
+            chain.clone(),
+            RESERVE_TOKEN.to_string(),
+            10_000_000 - 2 * RESERVES + 21_000,
+            sender.clone(),
+        )?;
+        assert_eq!(
+            inverse_bond.market(0)?.market.capacity,
+            Uint128::new(RESERVES - 21_000)
+        );
+
+        // The second one vests the reserves
+        inverse_bond.deposit(
+            sender.clone(),
+            1,
+            Decimal256::from_str("2.1")?,
+            &coins(10_000, ohm_denom.clone()),
+        )?;
+        assert_balance(chain.clone(), ohm_denom, 980_000, sender.clone())?;
+        let notes = inverse_bond.notes(sender.clone())?;
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].note.payout, Uint128::new(21_000));
+
+        chain.wait_seconds(3600)?;
+        inverse_bond.redeem(sender.clone())?;
+        assert_balance(
+            chain.clone(),
+            RESERVE_TOKEN.to_string(),
+            10_000_000 - 2 * RESERVES + 42_000,
+            sender.clone(),
+        )?;
+        assert!(inverse_bond.notes(sender)?.is_empty());
+
+        // The seller is protected from a lower price
+        let err = inverse_bond
+            .deposit(
+                chain.sender().to_string(),
+                0,
+                Decimal256::from_str("2.2")?,
+                &coins(10_000, shogun.staking.config()?.ohm_denom),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("less than min price"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn payouts_are_scaled_by_the_reserve_decimals() -> anyhow::Result<()> {
+        let (_, inverse_bond) = init_with_decimals(12)?;
+
+        // 0.01 OHM at 2.1 reserve tokens is 0.021 reserve tokens
+        assert_eq!(
+            inverse_bond.inverse_bond_quote(10_000u128.into(), 0)?,
+            InverseBondQuoteResponse {
+                price: Decimal256::from_str("2.1")?,
+                payout: 21_000_000_000u128.into(),
+            }
+        );
+        assert_eq!(
+            inverse_bond.decimals(AssetInfoUnchecked::native(RESERVE_TOKEN))?,
+            12
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn instant_markets_decay_their_debt_over_a_day() -> anyhow::Result<()> {
+        let (shogun, inverse_bond) = init()?;
+        let chain = inverse_bond.get_chain().clone();
+        let sender = chain.sender().to_string();
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+
+        inverse_bond.update_terms(
+            0,
+            Terms {
+                max_debt: 5_000u128.into(),
+                ..terms(0)?
+            },
+        )?;
+        inverse_bond.deposit(
+            sender.clone(),
+            0,
+            Decimal256::from_str("2.1")?,
+            &coins(10_000, ohm_denom.clone()),
+        )?;
+
+        // The debt of the instant payout is still there on the next deposit
+        let err = inverse_bond
+            .deposit(
+                sender.clone(),
+                0,
+                Decimal256::from_str("2.1")?,
+                &coins(10_000, ohm_denom.clone()),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("above its max debt"));
+
+        chain.wait_seconds(INSTANT_DECAY_LENGTH)?;
+        inverse_bond.deposit(
+            sender,
+            0,
+            Decimal256::from_str("2.1")?,
+            &coins(10_000, ohm_denom),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn closing_returns_reserves_to_the_treasury() -> anyhow::Result<()> {
+        let (_, inverse_bond) = init()?;
+        let chain = inverse_bond.get_chain().clone();
+        let treasury = inverse_bond.config()?.treasury;
+
+        inverse_bond.close_market(0)?;
+        assert_balance(chain.clone(), RESERVE_TOKEN.to_string(), RESERVES, treasury)?;
+        assert!(inverse_bond.market(0)?.market.capacity.is_zero());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn guardian_pauses_deposits_and_redemptions() -> anyhow::Result<()> {
+        let (shogun, inverse_bond) = init()?;
+        let mut chain = inverse_bond.get_chain().clone();
+        let sender = chain.sender().to_string();
+        let ohm_denom = shogun.staking.config()?.ohm_denom;
+        let guardian = chain.init_account(vec![])?;
+
+        inverse_bond.update_config(Some(guardian.address()), None, None)?;
+        inverse_bond.deposit(
+            sender.clone(),
+            1,
+            Decimal256::from_str("2.1")?,
+            &coins(10_000, ohm_denom.clone()),
+        )?;
+
+        inverse_bond
+            .call_as(&guardian)
+            .pause(vec![Operation::Deposit, Operation::Redeem])?;
+        assert_eq!(
+            inverse_bond.pause_status()?,
+            PauseStatus {
+                deposit: true,
+                redeem: true,
+            }
+        );
+        let err = inverse_bond
+            .deposit(
+                sender.clone(),
+                0,
+                Decimal256::from_str("2.1")?,
+                &coins(10_000, ohm_denom.clone()),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Deposit is paused"));
+        chain.wait_seconds(3600)?;
+        let err = inverse_bond.redeem(sender.clone()).unwrap_err();
+        assert!(err.to_string().contains("Redeem is paused"));
+
+        // Only the owner can unpause
+        inverse_bond
+            .call_as(&guardian)
+            .unpause(vec![Operation::Redeem])
+            .unwrap_err();
+        inverse_bond.unpause(vec![Operation::Redeem])?;
+        inverse_bond.redeem(sender)?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn governance_sudo_controls() -> anyhow::Result<()> {
+        let (shogun, inverse_bond) = init()?;
+        let mut chain = inverse_bond.get_chain().clone();
+        let new_owner = chain.init_account(vec![])?;
+        let recovery = chain.init_account(vec![])?;
+        let inverse_bond_addr = inverse_bond.address()?;
+
+        wasm_sudo(
+            &chain,
+            &inverse_bond_addr,
+            &SudoMsg::UpdateOwner {
+                owner: new_owner.address(),
+            },
+        )?;
+        assert_eq!(
+            inverse_bond.ownership()?.owner,
+            Some(Addr::unchecked(new_owner.address()))
+        );
+
+        wasm_sudo(
+            &chain,
+            &inverse_bond_addr,
+            &SudoMsg::Pause {
+                operations: vec![Operation::Deposit],
+            },
+        )?;
+        let err = inverse_bond
+            .deposit(
+                chain.sender().to_string(),
+                0,
+                Decimal256::from_str("2.1")?,
+                &coins(10_000, shogun.staking.config()?.ohm_denom),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Deposit is paused"));
+
+        // The reserves of both markets are held by the contract
+        wasm_sudo(
+            &chain,
+            &inverse_bond_addr,
+            &SudoMsg::SweepFunds {
+                recipient: recovery.address(),
+            },
+        )?;
+        assert_balance(
+            chain.clone(),
+            RESERVE_TOKEN.to_string(),
+            2 * RESERVES,
+            recovery.address(),
+        )?;
+
+        Ok(())
+    }
+}
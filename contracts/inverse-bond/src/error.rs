@@ -0,0 +1,62 @@
+use cosmwasm_std::{Binary, Response, StdError, Uint128};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ContractError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error(transparent)]
+    Bond(#[from] bond::ContractError),
+
+    #[error(transparent)]
+    AssetError(#[from] cw_asset::AssetError),
+
+    #[error(transparent)]
+    ConversionOverflowError(#[from] cosmwasm_std::ConversionOverflowError),
+
+    #[error(transparent)]
+    Ownership(#[from] cw_ownable::OwnershipError),
+
+    #[error("Unauthorized")]
+    Unauthorized {},
+
+    #[error("You need to send exactly one coin with this function with denom {0}")]
+    ReceiveOneCoin(String),
+
+    #[error("Not enough assets deposited expected: {expected}, got: {got}")]
+    NotEnoughDeposited { expected: Uint128, got: Uint128 },
+
+    #[error("Market {market_id} doesn't exist")]
+    MarketNotFound { market_id: u64 },
+
+    #[error("Market {market_id} is closed")]
+    MarketClosed { market_id: u64 },
+
+    #[error("Market {market_id} doesn't have enough reserves left")]
+    MarketCapacityExceeded { market_id: u64 },
+
+    #[error("Inverse bonds are priced by the oracle, they can't be sold in an auction")]
+    InvalidPricing {},
+
+    #[error("The market debt is above its max debt")]
+    MaxDebtReached {},
+
+    #[error("Slippage limit: less than min price")]
+    PriceBelowMin {},
+
+    #[error("Bond too small")]
+    BondTooSmall {},
+
+    #[error("Bond too large")]
+    BondTooLarge {},
+
+    #[error("Nothing to redeem")]
+    NothingToRedeem {},
+
+    #[error(transparent)]
+    Migrate(#[from] msgs::MigrateError),
+}
+
+pub type ContractResult<T = Response> = Result<T, ContractError>;
+pub type QueryResult = Result<Binary, ContractError>;
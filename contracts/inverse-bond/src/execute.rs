@@ -0,0 +1,168 @@
+use bond::{execute::vest, helpers::adjust, state::Note};
+use cosmwasm_std::{
+    ensure, to_json_binary, Addr, Coin, CosmosMsg, Decimal256, Deps, DepsMut, Env, MessageInfo,
+    Order, Response, StdResult, Uint128, WasmMsg,
+};
+use cw_asset::{Asset, AssetInfo};
+
+use crate::{
+    query::{debt_decay, inverse_bond_quote, max_payout, ohm_denom},
+    state::{
+        assert_not_paused, load_market, InverseMarket, Operation, CONFIG, MARKETS, NEXT_NOTE_ID,
+        NOTES, TERMS,
+    },
+    ContractError,
+};
+
+pub fn deposit_one_coin(info: MessageInfo, denom: String) -> Result<Uint128, ContractError> {
+    match info.funds.as_slice() {
+        [coin] if coin.denom == denom => Ok(coin.amount),
+        _ => Err(ContractError::ReceiveOneCoin(denom)),
+    }
+}
+
+pub fn decay_debt(
+    deps: DepsMut,
+    env: &Env,
+    market_id: u64,
+) -> Result<InverseMarket, ContractError> {
+    let debt_decay = debt_decay(deps.as_ref(), env, market_id)?;
+    let mut market = load_market(deps.storage, market_id)?;
+    market.total_debt -= debt_decay;
+    market.last_decay = env.block.time;
+    MARKETS.save(deps.storage, market_id, &market)?;
+
+    Ok(market)
+}
+
+/// Buys back the OHM sent by the depositor and burns it through the staking contract
+pub fn deposit(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    market_id: u64,
+    min_price: Decimal256,
+    depositor: String,
+) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Deposit)?;
+    let config = CONFIG.load(deps.storage)?;
+    let depositor = deps.api.addr_validate(&depositor)?;
+    let ohm_denom = ohm_denom(deps.as_ref())?;
+    let amount = deposit_one_coin(info, ohm_denom.clone())?;
+
+    let mut market = decay_debt(deps.branch(), &env, market_id)?;
+    ensure!(market.is_open(), ContractError::MarketClosed { market_id });
+    let terms = TERMS.load(deps.storage, market_id)?;
+    ensure!(
+        market.total_debt <= terms.max_debt,
+        ContractError::MaxDebtReached {}
+    );
+
+    let quote = inverse_bond_quote(deps.as_ref(), market_id, amount)?;
+    ensure!(quote.price >= min_price, ContractError::PriceBelowMin {});
+    ensure!(!quote.payout.is_zero(), ContractError::BondTooSmall {});
+    ensure!(
+        amount <= max_payout(deps.as_ref(), market_id)?,
+        ContractError::BondTooLarge {}
+    );
+    ensure!(
+        quote.payout <= market.capacity,
+        ContractError::MarketCapacityExceeded { market_id }
+    );
+
+    market.capacity -= quote.payout;
+    market.total_debt += amount;
+    market.burnt += amount;
+    market.paid += quote.payout;
+    MARKETS.save(deps.storage, market_id, &market)?;
+
+    let burn_msg = WasmMsg::Execute {
+        contract_addr: config.staking.to_string(),
+        msg: to_json_binary(&staking_contract::msg::ExecuteMsg::Burn {})?,
+        funds: vec![Coin {
+            denom: ohm_denom,
+            amount,
+        }],
+    };
+
+    let vesting_time_left = terms.vesting_time_left(env.block.time);
+    let mut res = Response::new()
+        .add_message(burn_msg)
+        .add_attribute("action", "inverse_deposit")
+        .add_attribute("market_id", market_id.to_string())
+        .add_attribute("amount", amount)
+        .add_attribute("payout", quote.payout);
+    if vesting_time_left == 0 {
+        res = res.add_message(Asset::new(market.reserve, quote.payout).transfer_msg(&depositor)?);
+    } else {
+        let note_id = NEXT_NOTE_ID.may_load(deps.storage)?.unwrap_or_default();
+        NEXT_NOTE_ID.save(deps.storage, &(note_id + 1))?;
+        NOTES.save(
+            deps.storage,
+            (&depositor, note_id),
+            &Note {
+                market_id,
+                payout: quote.payout,
+                vesting_time_left,
+                last_time: env.block.time,
+            },
+        )?;
+        res = res.add_attribute("note_id", note_id.to_string());
+    }
+
+    adjust(deps, env, market_id)?;
+    Ok(res)
+}
+
+/// Pays the vested reserves of all the recipient's notes
+pub fn redeem(deps: DepsMut, env: Env, recipient: String) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Redeem)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    let notes = NOTES
+        .prefix(&recipient)
+        .range(deps.storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let mut msgs: Vec<CosmosMsg> = vec![];
+    for (note_id, note) in notes {
+        let market = load_market(deps.storage, note.market_id)?;
+        let (payout, note) = vest(note, &env)?;
+        match note {
+            Some(note) => NOTES.save(deps.storage, (&recipient, note_id), &note)?,
+            None => NOTES.remove(deps.storage, (&recipient, note_id)),
+        }
+        if !payout.is_zero() {
+            msgs.push(Asset::new(market.reserve, payout).transfer_msg(&recipient)?);
+        }
+    }
+    ensure!(!msgs.is_empty(), ContractError::NothingToRedeem {});
+
+    Ok(Response::new()
+        .add_messages(msgs)
+        .add_attribute("action", "inverse_redeem")
+        .add_attribute("recipient", recipient))
+}
+
+/// Sends every native balance of the contract and the cw20 reserves of its markets to `recipient`
+pub fn sweep_msgs(
+    deps: Deps,
+    env: &Env,
+    recipient: &Addr,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let mut msgs = bond::helpers::sweep_msgs(deps, env, recipient)?;
+    let mut swept: Vec<AssetInfo> = vec![];
+    for market in MARKETS.range(deps.storage, None, None, Order::Ascending) {
+        let (_, market) = market?;
+        if !matches!(market.reserve, AssetInfo::Cw20(_)) || swept.contains(&market.reserve) {
+            continue;
+        }
+        let balance = market
+            .reserve
+            .query_balance(&deps.querier, &env.contract.address)?;
+        if !balance.is_zero() {
+            msgs.push(Asset::new(market.reserve.clone(), balance).transfer_msg(recipient)?);
+        }
+        swept.push(market.reserve);
+    }
+    Ok(msgs)
+}
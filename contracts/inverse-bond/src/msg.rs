@@ -0,0 +1,160 @@
+use bond::state::{Adjustment, Note, OracleConfig, Terms};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Decimal256, Uint128};
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+
+use crate::state::{InverseMarket, Operation, PauseStatus};
+
+/// Message type for `instantiate` entry_point
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub owner: Option<String>,
+    pub staking: String,
+    pub treasury: String,
+    pub guardian: Option<String>,
+}
+
+/// Message type for `execute` entry_point
+#[cw_ownable_execute]
+#[cw_serde]
+#[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
+pub enum ExecuteMsg {
+    /// Sells the OHM sent with the message for reserves, it is burnt right away.
+    /// The reserves are paid out instantly when the vesting length is zero, in a note otherwise
+    #[cfg_attr(feature = "interface", payable)]
+    Deposit {
+        market_id: u64,
+        min_price: Decimal256,
+        depositor: String,
+    },
+    /// Pays the vested part of all the recipient's notes
+    Redeem {
+        recipient: String,
+    },
+    /// Opens a market buying back OHM with `capacity` reserves of the treasury.
+    /// The owner sends them on behalf of the treasury, they are held by the market until they are
+    /// paid out or the market is closed. Native reserves are sent with the message, cw20 reserves
+    /// need an allowance.
+    /// The control variable of the terms is the premium paid over the oracle price
+    #[cfg_attr(feature = "interface", payable)]
+    CreateMarket {
+        reserve: cw_asset::AssetInfoBase<String>,
+        capacity: Uint128,
+        terms: Terms,
+        oracle: OracleConfig,
+    },
+    /// Stops all new deposits and sends the reserves left to the treasury
+    CloseMarket {
+        market_id: u64,
+    },
+    UpdateTerms {
+        market_id: u64,
+        terms: Terms,
+    },
+    UpdateConfig {
+        staking: Option<String>,
+        treasury: Option<String>,
+        guardian: Option<String>,
+    },
+    UpdateAdjustment {
+        market_id: u64,
+        add: Option<bool>,
+        rate: Option<Decimal256>,
+        target: Option<Decimal256>,
+        buffer: Option<u64>,
+    },
+    /// Registers the decimals of a reserve asset, they are queried from the chain when not set.
+    /// `None` removes them
+    SetDecimals {
+        asset: cw_asset::AssetInfoUnchecked,
+        decimals: Option<u8>,
+    },
+    /// Removes the guardian, only the owner can pause afterwards
+    RemoveGuardian {},
+    /// Can be called by the guardian or the owner
+    Pause {
+        operations: Vec<Operation>,
+    },
+    /// Can only be called by the owner
+    Unpause {
+        operations: Vec<Operation>,
+    },
+}
+
+/// Message type for `sudo` entry_point, only the chain governance can call it
+#[cw_serde]
+pub enum SudoMsg {
+    /// Replaces the owner without going through the two-step transfer
+    UpdateOwner {
+        owner: String,
+    },
+    Pause {
+        operations: Vec<Operation>,
+    },
+    Unpause {
+        operations: Vec<Operation>,
+    },
+    /// Sends all the reserves held by the contract to the recovery address
+    SweepFunds {
+        recipient: String,
+    },
+}
+
+/// Message type for `migrate` entry_point
+#[cw_serde]
+pub struct MigrateMsg {}
+
+/// Message type for `query` entry_point
+#[cw_ownable_query]
+#[cw_serde]
+#[derive(QueryResponses)]
+#[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    #[returns(Vec<MarketResponse>)]
+    Markets {},
+    #[returns(MarketResponse)]
+    Market { id: u64 },
+    #[returns(Adjustment)]
+    Adjustment { market_id: u64 },
+    /// Price and reserves paid for selling `amount` OHM to the market
+    #[returns(InverseBondQuoteResponse)]
+    InverseBondQuote { market_id: u64, amount: Uint128 },
+    #[returns(Vec<NoteResponse>)]
+    Notes { owner: String },
+    /// Decimals of a reserve asset
+    #[returns(u8)]
+    Decimals { asset: cw_asset::AssetInfoUnchecked },
+    #[returns(PauseStatus)]
+    PauseStatus {},
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub staking: String,
+    pub treasury: String,
+    pub guardian: Option<String>,
+}
+
+#[cw_serde]
+pub struct MarketResponse {
+    pub id: u64,
+    pub market: InverseMarket,
+    pub terms: Terms,
+}
+
+#[cw_serde]
+pub struct InverseBondQuoteResponse {
+    /// Reserves paid for one OHM
+    pub price: Decimal256,
+    pub payout: Uint128,
+}
+
+#[cw_serde]
+pub struct NoteResponse {
+    pub id: u64,
+    pub note: Note,
+    /// Vested reserves that can be redeemed now
+    pub pending_payout: Uint128,
+}
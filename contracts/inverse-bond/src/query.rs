@@ -0,0 +1,140 @@
+use std::cmp::max;
+
+use bond::{
+    query::{from_human, oracle_price, pending_payout, quote_decimals, to_human},
+    state::Adjustment,
+};
+use cosmwasm_std::{Decimal256, Deps, Env, Order, Uint128, Uint256};
+use staking_contract::{msg::ConfigResponse as StakingConfigResponse, state::OHM_DECIMALS};
+
+use crate::{
+    msg::{ConfigResponse, InverseBondQuoteResponse, MarketResponse, NoteResponse},
+    state::{load_market, ADJUSTMENTS, CONFIG, MARKETS, NOTES, TERMS},
+    ContractError,
+};
+
+pub fn ohm_denom(deps: Deps) -> Result<String, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let staking_config: StakingConfigResponse = deps
+        .querier
+        .query_wasm_smart(config.staking, &staking_contract::msg::QueryMsg::Config {})?;
+    Ok(staking_config.ohm_denom)
+}
+
+pub fn total_base_supply(deps: Deps) -> Result<Uint128, ContractError> {
+    Ok(deps.querier.query_supply(ohm_denom(deps)?)?.amount)
+}
+
+/// Seconds over which the debt of instant markets decays, so that their max debt still applies
+pub const INSTANT_DECAY_LENGTH: u64 = 86_400;
+
+pub fn debt_decay(deps: Deps, env: &Env, market_id: u64) -> Result<Uint128, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    let terms = TERMS.load(deps.storage, market_id)?;
+    let time_since_last_decay = env.block.time.seconds() - market.last_decay.seconds();
+    let decay_length = match terms.vesting_time_left(market.last_decay) {
+        0 => INSTANT_DECAY_LENGTH,
+        vesting_time_left => vesting_time_left,
+    };
+
+    Ok(market
+        .total_debt
+        .multiply_ratio(time_since_last_decay, decay_length)
+        .min(market.total_debt))
+}
+
+pub fn current_debt(deps: Deps, env: &Env, market_id: u64) -> Result<Uint128, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    Ok(market.total_debt - debt_decay(deps, env, market_id)?)
+}
+
+/// Reserves paid for one OHM, the oracle price plus the premium of the terms.
+/// The minimum price of the terms protects the sellers from a broken oracle
+pub fn inverse_bond_price(deps: Deps, market_id: u64) -> Result<Decimal256, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    let terms = TERMS.load(deps.storage, market_id)?;
+    let price = oracle_price(deps, &market.oracle, ohm_denom(deps)?, market.reserve_id())?;
+
+    Ok(max(
+        price * (Decimal256::one() + terms.control_variable),
+        terms.minimum_price,
+    ))
+}
+
+pub fn inverse_bond_quote(
+    deps: Deps,
+    market_id: u64,
+    amount: Uint128,
+) -> Result<InverseBondQuoteResponse, ContractError> {
+    let market = load_market(deps.storage, market_id)?;
+    let price = inverse_bond_price(deps, market_id)?;
+    // The price is in whole reserve tokens per whole OHM
+    let amount = to_human(amount, OHM_DECIMALS)?;
+    Ok(InverseBondQuoteResponse {
+        price,
+        payout: from_human(amount * price, quote_decimals(deps, &market.reserve)?)?,
+    })
+}
+
+/// Max OHM sold in one deposit, a share of the OHM supply
+pub fn max_payout(deps: Deps, market_id: u64) -> Result<Uint128, ContractError> {
+    let terms = TERMS.load(deps.storage, market_id)?;
+    Ok((Uint256::from(total_base_supply(deps)?) * terms.max_payout).try_into()?)
+}
+
+pub fn query_config(deps: Deps) -> Result<ConfigResponse, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+
+    Ok(ConfigResponse {
+        staking: config.staking.to_string(),
+        treasury: config.treasury.to_string(),
+        guardian: config.guardian.map(|g| g.to_string()),
+    })
+}
+
+pub fn query_market(deps: Deps, market_id: u64) -> Result<MarketResponse, ContractError> {
+    Ok(MarketResponse {
+        id: market_id,
+        market: load_market(deps.storage, market_id)?,
+        terms: TERMS.load(deps.storage, market_id)?,
+    })
+}
+
+pub fn query_markets(deps: Deps) -> Result<Vec<MarketResponse>, ContractError> {
+    MARKETS
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|market| {
+            let (id, market) = market?;
+            Ok(MarketResponse {
+                id,
+                market,
+                terms: TERMS.load(deps.storage, id)?,
+            })
+        })
+        .collect()
+}
+
+pub fn query_adjustment(deps: Deps, market_id: u64) -> Result<Adjustment, ContractError> {
+    load_market(deps.storage, market_id)?;
+    Ok(ADJUSTMENTS.load(deps.storage, market_id)?)
+}
+
+pub fn query_notes(
+    deps: Deps,
+    env: Env,
+    owner: String,
+) -> Result<Vec<NoteResponse>, ContractError> {
+    let owner = deps.api.addr_validate(&owner)?;
+    NOTES
+        .prefix(&owner)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| {
+            let (id, note) = item?;
+            Ok(NoteResponse {
+                id,
+                pending_payout: pending_payout(&note, &env)?,
+                note,
+            })
+        })
+        .collect()
+}
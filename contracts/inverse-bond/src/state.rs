@@ -0,0 +1,74 @@
+use bond::state::{Note, OracleConfig};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Storage, Timestamp, Uint128};
+use cw_asset::AssetInfo;
+use cw_storage_plus::{Item, Map};
+
+use crate::ContractError;
+
+// The terms, adjustments, decimals and pause status are stored under the same keys as in the
+// bond depository, so that its `TERMS`, `ADJUSTMENTS`, `adjust`, `quote_decimals` and pause
+// helpers can be used as is
+pub use bond::state::{
+    assert_not_paused, pause_status, set_paused, Operation, PauseStatus, ADJUSTMENTS, DECIMALS,
+    PAUSED, TERMS,
+};
+
+pub const CONFIG: Item<Config> = Item::new("config");
+pub const NEXT_MARKET_ID: Item<u64> = Item::new("next_market_id");
+pub const MARKETS: Map<u64, InverseMarket> = Map::new("markets");
+
+pub const NEXT_NOTE_ID: Item<u64> = Item::new("next_note_id");
+/// Notes of each owner, their payout is in the reserve asset of the market
+pub const NOTES: Map<(&Addr, u64), Note> = Map::new("notes");
+
+#[cw_serde]
+pub struct Config {
+    /// Staking contract, it burns the OHM bought back
+    pub staking: Addr,
+    /// Receives the reserves left in closed markets
+    pub treasury: Addr,
+    /// Can pause deposits and redemptions
+    pub guardian: Option<Addr>,
+}
+
+/// Market buying back OHM with reserves of the treasury. They are escrowed by the market when
+/// the owner creates it, and the reserves left go back to the treasury when it's closed
+#[cw_serde]
+pub struct InverseMarket {
+    /// Native denom or cw20 token paid out by the market
+    pub reserve: AssetInfo,
+    /// Reserves left to pay out, the market is closed once it reaches zero
+    pub capacity: Uint128,
+    /// OHM debt of the market, it decays over the vesting length as in the bond depository,
+    /// over `INSTANT_DECAY_LENGTH` for instant payouts
+    pub total_debt: Uint128,
+    pub last_decay: Timestamp,
+    /// OHM burnt by the market
+    pub burnt: Uint128,
+    /// Reserves paid out by the market
+    pub paid: Uint128,
+    /// Market price of OHM in the reserve asset
+    pub oracle: OracleConfig,
+}
+
+impl InverseMarket {
+    pub fn is_open(&self) -> bool {
+        !self.capacity.is_zero()
+    }
+
+    /// Denom or cw20 address of the reserve, used for oracle prices
+    pub fn reserve_id(&self) -> String {
+        match &self.reserve {
+            AssetInfo::Native(denom) => denom.clone(),
+            AssetInfo::Cw20(addr) => addr.to_string(),
+            info => info.to_string(),
+        }
+    }
+}
+
+pub fn load_market(storage: &dyn Storage, market_id: u64) -> Result<InverseMarket, ContractError> {
+    MARKETS
+        .may_load(storage, market_id)?
+        .ok_or(ContractError::MarketNotFound { market_id })
+}
@@ -2,13 +2,22 @@ use crate::execute::{execute_lock, execute_unlock};
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
 use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response};
+use msgs::{assert_can_migrate, migrate_attributes};
 
 use crate::error::ContractError;
-use crate::msg::{AcceptedTokenUnchecked, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::helpers::sweep_msgs;
+use crate::migrate::{lock_amount_changed, migrate_storage};
+use crate::msg::{
+    AcceptedTokenUnchecked, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg,
+};
 use crate::query::{
-    locks_for_address, query_accepted_tokens, query_available_unlock, query_config, query_lock,
+    locks_for_address, query_accepted_tokens, query_available_unlock, query_config,
+    query_deposit_fee, query_lock,
 };
-use crate::state::{Config, ACCEPTED_TOKENS, CONFIG};
+use crate::state::{pause_status, set_paused, Config, Operation, ACCEPTED_TOKENS, CONFIG};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -17,13 +26,16 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner = msg.owner.unwrap_or_else(|| info.sender.to_string());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+
     let config = Config {
-        admin: msg
-            .admin
-            .map(|addr| deps.api.addr_validate(&addr))
-            .transpose()?
-            .unwrap_or(info.sender),
         next_deposit_id: 0,
+        guardian: msg
+            .guardian
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
     };
 
     msg.accepted_tokens
@@ -49,12 +61,19 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { admin } => update_config(deps, info, admin),
+        ExecuteMsg::UpdateConfig { guardian } => update_config(deps, info, guardian),
         ExecuteMsg::UpdateAcceptedToken { to_add, to_remove } => {
             update_accepted_token(deps, info, to_add, to_remove)
         }
         ExecuteMsg::Lock { to, asset, lock } => execute_lock(deps, env, info, to, asset, lock),
         ExecuteMsg::Unlock { to, id } => execute_unlock(deps, env, info, to, id),
+        ExecuteMsg::RemoveGuardian {} => remove_guardian(deps, info),
+        ExecuteMsg::Pause { operations } => pause(deps, info, operations),
+        ExecuteMsg::Unpause { operations } => unpause(deps, info, operations),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
     }
 }
 
@@ -66,6 +85,9 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::AcceptedTokens { start, limit } => Ok(to_json_binary(&query_accepted_tokens(
             deps, env, start, limit,
         )?)?),
+        QueryMsg::DepositFee { asset } => {
+            Ok(to_json_binary(&query_deposit_fee(deps, env, asset)?)?)
+        }
         QueryMsg::Lock { id } => Ok(to_json_binary(&query_lock(deps, env, id)?)?),
         QueryMsg::AvailableUnlock { id } => {
             Ok(to_json_binary(&query_available_unlock(deps, env, id)?)?)
@@ -77,20 +99,65 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         } => Ok(to_json_binary(&locks_for_address(
             deps, env, address, start, limit,
         )?)?),
+        QueryMsg::PauseStatus {} => Ok(to_json_binary(&pause_status(deps.storage)?)?),
+        QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
     }
 }
 
+/// Handling governance calls, they bypass the owner and guardian checks
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::UpdateOwner { owner } => {
+            let ownership = cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+            Ok(Response::new()
+                .add_attribute("action", "sudo_update_owner")
+                .add_attributes(ownership.into_attributes()))
+        }
+        SudoMsg::Pause { operations } => {
+            set_paused(deps.storage, &operations, true)?;
+            Ok(Response::new().add_attribute("action", "sudo_pause"))
+        }
+        SudoMsg::Unpause { operations } => {
+            set_paused(deps.storage, &operations, false)?;
+            Ok(Response::new().add_attribute("action", "sudo_unpause"))
+        }
+        SudoMsg::SweepFunds { recipient } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            Ok(Response::new()
+                .add_messages(sweep_msgs(deps.as_ref(), &env, &recipient)?)
+                .add_attribute("action", "sudo_sweep_funds")
+                .add_attribute("recipient", recipient))
+        }
+    }
+}
+
+/// Handling contract migration
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    migrate_storage(deps.branch(), from_version.as_ref())?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    let mut res =
+        Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION));
+    // Flags the breaking change of the `Lock` amount for the integrators
+    if lock_amount_changed(from_version.as_ref()) {
+        res = res.add_attribute("lock_amount", "before_fee");
+    }
+    Ok(res)
+}
+
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
-    admin: Option<String>,
+    guardian: Option<String>,
 ) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
     let mut config = CONFIG.load(deps.storage)?;
-    if info.sender != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
-    if let Some(admin) = admin {
-        config.admin = deps.api.addr_validate(&admin)?;
+    if let Some(guardian) = guardian {
+        config.guardian = Some(deps.api.addr_validate(&guardian)?);
     }
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new())
@@ -101,10 +168,7 @@ pub fn update_accepted_token(
     to_add: Vec<AcceptedTokenUnchecked>,
     to_remove: Vec<cw_asset::AssetInfoBase<String>>,
 ) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    if info.sender != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
     to_add.iter().try_for_each(|a| {
         let checked_token = a.check(deps.api)?;
         ACCEPTED_TOKENS.save(
@@ -123,3 +187,191 @@ pub fn update_accepted_token(
 
     Ok(Response::new())
 }
+
+pub fn remove_guardian(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.guardian = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "remove_guardian"))
+}
+
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !cw_ownable::is_owner(deps.storage, &info.sender)?
+        && Some(&info.sender) != config.guardian.as_ref()
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    set_paused(deps.storage, &operations, true)?;
+
+    Ok(Response::new().add_attribute("action", "pause"))
+}
+
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    set_paused(deps.storage, &operations, false)?;
+
+    Ok(Response::new().add_attribute("action", "unpause"))
+}
+
+#[cfg(test)]
+pub mod test {
+    use cosmwasm_std::{coin, coins, Decimal256};
+    use cw_asset::{AssetBase, AssetInfoBase};
+    use cw_orch::injective_test_tube::injective_test_tube::Account;
+    use cw_orch::{injective_test_tube::InjectiveTestTube, prelude::*};
+
+    use lock_contract::interface::LockContract;
+    use lock_contract::msg::{
+        AcceptedTokenUnchecked, ExecuteMsgFns, InstantiateMsg, QueryMsgFns, SudoMsg,
+    };
+    use lock_contract::state::{deposit::DepositLock, Fee, Operation};
+    use tests::sudo::wasm_sudo;
+    use tests::tokenfactory::assert_balance;
+
+    pub const AMOUNT_TO_CREATE_DENOM: u128 = 10_000_000_000_000_000_000u128;
+    pub const FUNDS_MULTIPLIER: u128 = 100_000;
+    pub const LOCK_TOKEN: &str = "ulock";
+
+    pub fn init() -> anyhow::Result<LockContract<InjectiveTestTube>> {
+        let chain = InjectiveTestTube::new(vec![
+            coin(AMOUNT_TO_CREATE_DENOM * FUNDS_MULTIPLIER, "inj"),
+            coin(10_000_000, LOCK_TOKEN),
+        ]);
+
+        let contract = LockContract::new("lock", chain.clone());
+        contract.upload()?;
+        contract.instantiate(
+            &InstantiateMsg {
+                owner: None,
+                accepted_tokens: vec![AcceptedTokenUnchecked {
+                    asset: AssetInfoBase::native(LOCK_TOKEN),
+                    deposit_fee: Fee::Variable(Decimal256::one()),
+                }],
+                guardian: None,
+            },
+            None,
+            None,
+        )?;
+
+        Ok(contract)
+    }
+
+    pub fn lock<Chain: CwEnv>(contract: &LockContract<Chain>, amount: u128) -> anyhow::Result<()> {
+        contract.lock(
+            AssetBase::native(LOCK_TOKEN, amount),
+            DepositLock::TimeUnlock(1_000),
+            contract.get_chain().sender().to_string(),
+            &coins(amount, LOCK_TOKEN),
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn deposit_fees_are_taken_from_the_lock() -> anyhow::Result<()> {
+        let contract = init()?;
+        let chain = contract.get_chain().clone();
+        let sender = chain.sender().to_string();
+
+        contract.update_accepted_token(
+            vec![AcceptedTokenUnchecked {
+                asset: AssetInfoBase::native(LOCK_TOKEN),
+                deposit_fee: Fee::Fixed(1_000u128.into()),
+            }],
+            vec![],
+        )?;
+        assert_eq!(
+            contract.deposit_fee(AssetInfoBase::native(LOCK_TOKEN))?,
+            Fee::Fixed(1_000u128.into())
+        );
+
+        // The depositor sends the amount before the fee
+        lock(&contract, 10_000)?;
+        let locks = contract.locks_for_address(sender.clone(), None, None)?;
+        assert_eq!(locks[0].asset, AssetBase::native(LOCK_TOKEN, 9_000u128));
+
+        // Deposits have to cover the fee
+        lock(&contract, 500).unwrap_err();
+
+        contract.update_accepted_token(
+            vec![AcceptedTokenUnchecked {
+                asset: AssetInfoBase::native(LOCK_TOKEN),
+                deposit_fee: Fee::Variable(Decimal256::percent(90)),
+            }],
+            vec![],
+        )?;
+        lock(&contract, 10_000)?;
+        let locks = contract.locks_for_address(sender, None, None)?;
+        assert_eq!(locks[0].asset, AssetBase::native(LOCK_TOKEN, 9_000u128));
+        assert_eq!(locks.len(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn governance_sudo_controls() -> anyhow::Result<()> {
+        let contract = init()?;
+        let mut chain = contract.get_chain().clone();
+        let new_owner = chain.init_account(vec![])?;
+        let recovery = chain.init_account(vec![])?;
+        let lock_addr = contract.address()?;
+
+        wasm_sudo(
+            &chain,
+            &lock_addr,
+            &SudoMsg::UpdateOwner {
+                owner: new_owner.address().to_string(),
+            },
+        )?;
+        assert_eq!(
+            contract.ownership()?.owner,
+            Some(Addr::unchecked(new_owner.address()))
+        );
+
+        wasm_sudo(
+            &chain,
+            &lock_addr,
+            &SudoMsg::Pause {
+                operations: vec![Operation::Lock],
+            },
+        )?;
+        let err = lock(&contract, 10_000).unwrap_err();
+        assert!(err.to_string().contains("Lock is paused"));
+
+        wasm_sudo(
+            &chain,
+            &lock_addr,
+            &SudoMsg::Unpause {
+                operations: vec![Operation::Lock],
+            },
+        )?;
+        lock(&contract, 10_000)?;
+
+        wasm_sudo(
+            &chain,
+            &lock_addr,
+            &SudoMsg::SweepFunds {
+                recipient: recovery.address().to_string(),
+            },
+        )?;
+        assert_balance(
+            chain.clone(),
+            LOCK_TOKEN.to_string(),
+            10_000,
+            recovery.address().to_string(),
+        )?;
+
+        Ok(())
+    }
+}
@@ -2,6 +2,8 @@ use cosmwasm_std::{Addr, StdError, Uint128};
 use cw_asset::AssetInfo;
 use thiserror::Error;
 
+use crate::state::Operation;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -22,6 +24,9 @@ pub enum ContractError {
     #[error(transparent)]
     AssetError(#[from] cw_asset::AssetError),
 
+    #[error(transparent)]
+    Ownership(#[from] cw_ownable::OwnershipError),
+
     #[error("You need to send exactly one coin with this function with denom {0}")]
     ReceiveOneCoin(String),
 
@@ -39,4 +44,10 @@ pub enum ContractError {
 
     #[error("Not enough assets deposited expected: {expected}, got: {got}")]
     NotEnoughDeposited { expected: Uint128, got: Uint128 },
+
+    #[error("{operation:?} is paused")]
+    Paused { operation: Operation },
+
+    #[error(transparent)]
+    Migrate(#[from] msgs::MigrateError),
 }
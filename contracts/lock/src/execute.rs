@@ -5,8 +5,9 @@ use crate::{
     helpers::deposit_one_coin,
     query::_available_unlock,
     state::{
+        assert_not_paused,
         deposit::{deposits, DepositInfo, DepositLock},
-        ACCEPTED_TOKENS, CONFIG,
+        Operation, ACCEPTED_TOKENS, CONFIG,
     },
     ContractError,
 };
@@ -19,6 +20,7 @@ pub fn execute_lock(
     token: AssetBase<String>,
     lock: DepositLock,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Lock)?;
     let to_addr = deps.api.addr_validate(&to)?;
     let token = token.check(deps.api, None)?;
 
@@ -28,7 +30,23 @@ pub fn execute_lock(
         },
     ))?;
 
-    // We apply the fee
+    // We transfer the tokens, the depositor sends the amount before the fee
+    let msg = match &token.info {
+        AssetInfo::Cw20(_) => Some(token.transfer_from_msg(info.sender, env.contract.address)?),
+        AssetInfo::Native(denom) => {
+            let amount = deposit_one_coin(info, denom.to_string())?;
+            if amount != token.amount {
+                return Err(ContractError::NotEnoughDeposited {
+                    expected: token.amount,
+                    got: amount,
+                });
+            }
+            None
+        }
+        _ => return Err(ContractError::AssetNotAccepted { token: token.info }),
+    };
+
+    // We apply the fee, it stays in the contract
     let token = fee.apply(token)?;
 
     // We save the deposit info
@@ -46,26 +64,10 @@ pub fn execute_lock(
             id: next_deposit_id,
             lock,
             recipient: to_addr,
-            asset: token.clone(),
+            asset: token,
         },
     )?;
 
-    // We transfer the tokens
-    let msg = match &token.info {
-        AssetInfo::Cw20(_) => Some(token.transfer_from_msg(info.sender, env.contract.address)?),
-        AssetInfo::Native(denom) => {
-            let amount = deposit_one_coin(info, denom.to_string())?;
-            if amount != token.amount {
-                return Err(ContractError::NotEnoughDeposited {
-                    expected: token.amount,
-                    got: amount,
-                });
-            }
-            None
-        }
-        _ => return Err(ContractError::AssetNotAccepted { token: token.info }),
-    };
-
     Ok(Response::new().add_messages(msg))
 }
 
@@ -76,6 +78,7 @@ pub fn execute_unlock(
     to: String,
     id: u64,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Unlock)?;
     let mut deposit_info = deposits().load(deps.storage, id)?;
 
     if info.sender != deposit_info.recipient && to != deposit_info.recipient {
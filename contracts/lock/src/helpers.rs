@@ -1,8 +1,9 @@
 use cosmos_sdk_proto::traits::Message;
-use cosmwasm_std::{Coin, CosmosMsg, Env, MessageInfo, Uint128};
+use cosmwasm_std::{Addr, BankMsg, Coin, CosmosMsg, Deps, Env, MessageInfo, Order, Uint128};
+use cw_asset::{Asset, AssetInfo};
 use injective_std::types::injective::tokenfactory::v1beta1::{MsgCreateDenom, MsgMint};
 
-use crate::ContractError;
+use crate::{state::ACCEPTED_TOKENS, ContractError};
 
 pub fn deposit_one_coin(info: MessageInfo, denom: String) -> Result<Uint128, ContractError> {
     // Verify the funds
@@ -57,3 +58,32 @@ pub fn mint_msgs(
         }),
     ]
 }
+
+/// Sends every native balance and every accepted cw20 balance of the contract to `recipient`
+pub fn sweep_msgs(
+    deps: Deps,
+    env: &Env,
+    recipient: &Addr,
+) -> Result<Vec<CosmosMsg>, ContractError> {
+    let mut msgs = vec![];
+
+    let balances = deps.querier.query_all_balances(&env.contract.address)?;
+    if !balances.is_empty() {
+        msgs.push(CosmosMsg::Bank(BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: balances,
+        }));
+    }
+
+    for token in ACCEPTED_TOKENS.keys(deps.storage, None, None, Order::Ascending) {
+        let token = token?;
+        if let AssetInfo::Cw20(_) = token {
+            let amount = token.query_balance(&deps.querier, &env.contract.address)?;
+            if !amount.is_zero() {
+                msgs.push(Asset::new(token, amount).transfer_msg(recipient)?);
+            }
+        }
+    }
+
+    Ok(msgs)
+}
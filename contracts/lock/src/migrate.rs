@@ -0,0 +1,87 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, DepsMut};
+use cw_storage_plus::Item;
+use semver::Version;
+
+use crate::{
+    state::{Config, CONFIG},
+    ContractError,
+};
+
+/// Config layout used before cw2 versioning, the admin was stored in the config
+#[cw_serde]
+struct LegacyConfig {
+    pub admin: Addr,
+    pub next_deposit_id: u64,
+}
+
+const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// First version taking the `Lock` amount before the deposit fee. The stored deposits keep
+/// their meaning, only the callers change
+pub const FEE_INCLUSIVE_LOCK: Version = Version::new(0, 1, 0);
+
+/// Whether the callers of `Lock` have to move to amounts before the deposit fee
+pub fn lock_amount_changed(from: Option<&Version>) -> bool {
+    from.map_or(true, |from| from < &FEE_INCLUSIVE_LOCK)
+}
+
+/// Transforms the storage written by `from` into the current layout.
+/// `from` is `None` for contracts instantiated before cw2 versioning
+pub fn migrate_storage(deps: DepsMut, from: Option<&Version>) -> Result<(), ContractError> {
+    if from.is_none() {
+        migrate_legacy_config(deps)?;
+    }
+    Ok(())
+}
+
+/// Moves the admin from the config to cw-ownable
+fn migrate_legacy_config(deps: DepsMut) -> Result<(), ContractError> {
+    let legacy = LEGACY_CONFIG.load(deps.storage)?;
+
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(legacy.admin.as_str()))?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            next_deposit_id: legacy.next_deposit_id,
+            guardian: None,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{Addr, Attribute, Storage};
+
+    use crate::contract::{migrate, CONTRACT_NAME, CONTRACT_VERSION};
+    use crate::msg::MigrateMsg;
+    use crate::state::CONFIG;
+
+    #[test]
+    pub fn legacy_admin_becomes_the_owner() -> anyhow::Result<()> {
+        let mut deps = mock_dependencies();
+        deps.storage
+            .set(b"config", br#"{"admin":"admin","next_deposit_id":3}"#);
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {})?;
+
+        let version = cw2::get_contract_version(&deps.storage)?;
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
+        assert_eq!(
+            cw_ownable::get_ownership(&deps.storage)?.owner,
+            Some(Addr::unchecked("admin"))
+        );
+        let config = CONFIG.load(&deps.storage)?;
+        assert_eq!(config.next_deposit_id, 3);
+        assert_eq!(config.guardian, None);
+        assert!(res
+            .attributes
+            .contains(&Attribute::new("lock_amount", "before_fee")));
+
+        Ok(())
+    }
+}
@@ -1,16 +1,18 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
 use cw_asset::{AssetBase, AssetInfoBase};
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
 
 use crate::state::{
     deposit::{DepositInfo, DepositLock},
-    Fee,
+    Fee, Operation, PauseStatus,
 };
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub admin: Option<String>,
+    pub owner: Option<String>,
     pub accepted_tokens: Vec<AcceptedTokenUnchecked>,
+    pub guardian: Option<String>,
 }
 
 #[cw_serde]
@@ -20,6 +22,7 @@ pub struct AcceptedTokenUnchecked {
 }
 
 /// Message type for `execute` entry_point
+#[cw_ownable_execute]
 #[cw_serde]
 #[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
@@ -27,6 +30,8 @@ pub enum ExecuteMsg {
         to_add: Vec<AcceptedTokenUnchecked>,
         to_remove: Vec<cw_asset::AssetInfoBase<String>>,
     },
+    /// `asset` is the amount before the deposit fee, it has to be sent in full.
+    /// The fee stays in the contract and the rest is locked for `to`
     #[cfg_attr(feature = "interface", payable)]
     Lock {
         to: String,
@@ -38,15 +43,45 @@ pub enum ExecuteMsg {
         id: u64,
     },
     UpdateConfig {
-        admin: Option<String>,
+        guardian: Option<String>,
+    },
+    /// Removes the guardian, only the owner can pause afterwards
+    RemoveGuardian {},
+    /// Can be called by the guardian or the owner
+    Pause {
+        operations: Vec<Operation>,
+    },
+    /// Can only be called by the owner
+    Unpause {
+        operations: Vec<Operation>,
+    },
+}
+
+/// Message type for `sudo` entry_point, only the chain governance can call it
+#[cw_serde]
+pub enum SudoMsg {
+    /// Replaces the owner without going through the two-step transfer
+    UpdateOwner {
+        owner: String,
+    },
+    Pause {
+        operations: Vec<Operation>,
+    },
+    Unpause {
+        operations: Vec<Operation>,
+    },
+    /// Sends all the native funds and accepted cw20 tokens held by the contract to the recovery address
+    SweepFunds {
+        recipient: String,
     },
 }
 
 /// Message type for `migrate` entry_point
 #[cw_serde]
-pub enum MigrateMsg {}
+pub struct MigrateMsg {}
 
 /// Message type for `query` entry_point
+#[cw_ownable_query]
 #[cw_serde]
 #[derive(QueryResponses)]
 #[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
@@ -58,6 +93,9 @@ pub enum QueryMsg {
         start: Option<AssetInfoBase<String>>,
         limit: Option<u32>,
     },
+    /// Fee applied to the deposits of an accepted token
+    #[returns(Fee)]
+    DepositFee { asset: AssetInfoBase<String> },
     #[returns(DepositInfoResponse)]
     Lock { id: u64 },
     #[returns(AssetBase<String>)]
@@ -68,11 +106,13 @@ pub enum QueryMsg {
         start: Option<u64>,
         limit: Option<u32>,
     },
+    #[returns(PauseStatus)]
+    PauseStatus {},
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
-    pub admin: String,
+    pub guardian: Option<String>,
 }
 
 #[cw_serde]
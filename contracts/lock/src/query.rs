@@ -7,7 +7,7 @@ use crate::{
     msg::{AcceptedTokenUnchecked, ConfigResponse, DepositInfoResponse},
     state::{
         deposit::{deposits, DepositInfo},
-        ACCEPTED_TOKENS, CONFIG,
+        Fee, ACCEPTED_TOKENS, CONFIG,
     },
     ContractError,
 };
@@ -16,7 +16,7 @@ pub fn query_config(deps: Deps, _env: Env) -> Result<ConfigResponse, ContractErr
     let config = CONFIG.load(deps.storage)?;
 
     Ok(ConfigResponse {
-        admin: config.admin.to_string(),
+        guardian: config.guardian.map(|g| g.to_string()),
     })
 }
 
@@ -42,6 +42,17 @@ pub fn query_accepted_tokens(
     )
 }
 
+pub fn query_deposit_fee(
+    deps: Deps,
+    _env: Env,
+    asset: AssetInfoBase<String>,
+) -> Result<Fee, ContractError> {
+    let asset = asset.check(deps.api, None)?;
+    ACCEPTED_TOKENS
+        .may_load(deps.storage, &asset)?
+        .ok_or(ContractError::AssetNotAccepted { token: asset })
+}
+
 pub fn query_lock(deps: Deps, _env: Env, id: u64) -> Result<DepositInfoResponse, ContractError> {
     let stored_info = deposits().load(deps.storage, id)?;
 
@@ -2,7 +2,7 @@
 // see: https://crates.io/crates/cw-storage-plus
 
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Api, Decimal256, Uint128, Uint256};
+use cosmwasm_std::{Addr, Api, Decimal256, Storage, Uint128, Uint256};
 use cw_asset::{Asset, AssetInfo};
 use cw_storage_plus::{Item, Map};
 
@@ -10,6 +10,7 @@ use crate::{msg::AcceptedTokenUnchecked, ContractError};
 
 pub const CONFIG: Item<Config> = Item::new("config");
 pub const ACCEPTED_TOKENS: Map<&AssetInfo, Fee> = Map::new("accepted_tokens");
+pub const PAUSED: Item<PauseStatus> = Item::new("paused");
 
 pub mod deposit {
     use cosmwasm_schema::cw_serde;
@@ -75,8 +76,60 @@ pub mod deposit {
 
 #[cw_serde]
 pub struct Config {
-    pub admin: Addr,
     pub next_deposit_id: u64,
+    pub guardian: Option<Addr>,
+}
+
+/// Operations the guardian can pause
+#[cw_serde]
+pub enum Operation {
+    Lock,
+    Unlock,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseStatus {
+    pub lock: bool,
+    pub unlock: bool,
+}
+
+impl PauseStatus {
+    pub fn is_paused(&self, operation: &Operation) -> bool {
+        match operation {
+            Operation::Lock => self.lock,
+            Operation::Unlock => self.unlock,
+        }
+    }
+
+    pub fn set(&mut self, operation: &Operation, paused: bool) {
+        match operation {
+            Operation::Lock => self.lock = paused,
+            Operation::Unlock => self.unlock = paused,
+        }
+    }
+}
+
+pub fn pause_status(storage: &dyn Storage) -> Result<PauseStatus, ContractError> {
+    Ok(PAUSED.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn set_paused(
+    storage: &mut dyn Storage,
+    operations: &[Operation],
+    paused: bool,
+) -> Result<(), ContractError> {
+    let mut status = pause_status(storage)?;
+    operations.iter().for_each(|op| status.set(op, paused));
+    PAUSED.save(storage, &status)?;
+    Ok(())
+}
+
+pub fn assert_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    if pause_status(storage)?.is_paused(&operation) {
+        return Err(ContractError::Paused { operation });
+    }
+    Ok(())
 }
 
 #[cw_serde]
@@ -96,15 +149,18 @@ impl AcceptedTokenUnchecked {
 
 #[cw_serde]
 pub enum Fee {
+    /// Amount taken from every deposit
     Fixed(Uint128),
+    /// Share of every deposit that is locked
     Variable(Decimal256),
 }
 
 impl Fee {
+    /// Amount of `asset` left once the fee is taken
     pub fn apply(&self, mut asset: Asset) -> Result<Asset, ContractError> {
         match self {
             Fee::Fixed(f) => {
-                if f < &asset.amount {
+                if asset.amount < *f {
                     return Err(ContractError::NotEnoughDeposited {
                         expected: *f,
                         got: asset.amount,
@@ -0,0 +1,43 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{to_json_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cw_storage_plus::Item;
+
+use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+
+pub const NOTIFICATIONS: Item<u64> = Item::new("notifications");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> StdResult<Response> {
+    NOTIFICATIONS.save(deps.storage, &0)?;
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::StakeChangedHook(_) => {
+            // Writes until the gas runs out, the notification never completes
+            loop {
+                NOTIFICATIONS.update(deps.storage, |n| StdResult::Ok(n + 1))?;
+            }
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Notifications {} => to_json_binary(&NOTIFICATIONS.load(deps.storage)?),
+    }
+}
@@ -0,0 +1,6 @@
+//! Staking hook burning all the gas it is given.
+//! It is only meant for tests
+pub mod contract;
+#[cfg(feature = "interface")]
+pub mod interface;
+pub mod msg;
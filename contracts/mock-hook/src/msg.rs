@@ -0,0 +1,17 @@
+use cosmwasm_schema::{cw_serde, QueryResponses};
+
+pub use staking_contract::msg::StakeChangedExecuteMsg as ExecuteMsg;
+
+/// Message type for `instantiate` entry_point
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+/// Message type for `query` entry_point
+#[cw_serde]
+#[derive(QueryResponses)]
+#[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
+pub enum QueryMsg {
+    /// Number of notifications that went through, always 0 when the hook runs out of gas
+    #[returns(u64)]
+    Notifications {},
+}
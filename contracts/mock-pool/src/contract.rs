@@ -0,0 +1,61 @@
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Addr, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult, Uint128,
+};
+use cw20::TokenInfoResponse;
+use cw_storage_plus::Item;
+
+use crate::msg::{ExecuteMsg, InstantiateMsg, PoolResponse, QueryMsg};
+
+pub const LP_TOKEN: Item<Addr> = Item::new("lp_token");
+pub const ASSETS: Item<[String; 2]> = Item::new("assets");
+pub const DECIMALS: Item<[u8; 2]> = Item::new("decimals");
+pub const RESERVES: Item<[Uint128; 2]> = Item::new("reserves");
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: InstantiateMsg,
+) -> StdResult<Response> {
+    LP_TOKEN.save(deps.storage, &deps.api.addr_validate(&msg.lp_token)?)?;
+    ASSETS.save(deps.storage, &msg.assets)?;
+    DECIMALS.save(deps.storage, &msg.decimals)?;
+    RESERVES.save(deps.storage, &msg.reserves)?;
+    Ok(Response::new())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: ExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        ExecuteMsg::SetReserves { reserves } => {
+            RESERVES.save(deps.storage, &reserves)?;
+            Ok(Response::new().add_attribute("action", "set_reserves"))
+        }
+    }
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Pool {} => {
+            let token_info: TokenInfoResponse = deps.querier.query_wasm_smart(
+                LP_TOKEN.load(deps.storage)?,
+                &cw20_base::msg::QueryMsg::TokenInfo {},
+            )?;
+            to_json_binary(&PoolResponse {
+                assets: ASSETS.load(deps.storage)?,
+                decimals: DECIMALS.load(deps.storage)?,
+                reserves: RESERVES.load(deps.storage)?,
+                total_share: token_info.total_supply,
+            })
+        }
+    }
+}
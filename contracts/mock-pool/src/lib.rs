@@ -0,0 +1,6 @@
+//! Two-asset pool answering the queries of the bonding calculator.
+//! The reserves are set by hand, it is only meant for tests
+pub mod contract;
+#[cfg(feature = "interface")]
+pub mod interface;
+pub mod msg;
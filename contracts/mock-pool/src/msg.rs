@@ -0,0 +1,22 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::Uint128;
+
+pub use bonding_calculator::msg::{PoolQueryMsg as QueryMsg, PoolResponse};
+
+/// Message type for `instantiate` entry_point
+#[cw_serde]
+pub struct InstantiateMsg {
+    /// cw20 LP token of the pool, its supply is the total share
+    pub lp_token: String,
+    pub assets: [String; 2],
+    pub decimals: [u8; 2],
+    pub reserves: [Uint128; 2],
+}
+
+/// Message type for `execute` entry_point
+#[cw_serde]
+#[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
+pub enum ExecuteMsg {
+    /// Replaces the reserves of the pool, anyone can call it
+    SetReserves { reserves: [Uint128; 2] },
+}
@@ -1,18 +1,22 @@
 use crate::error::ContractError;
+use crate::migrate::migrate_storage;
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceResponse, PricesResponse,
-    PricesResponseElem, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, MigrateMsg, PriceResponse,
+    PricesResponse, PricesResponseElem, QueryMsg,
 };
 use crate::state::{
-    read_config, read_feeder, read_price, read_prices, store_config, store_feeder, store_price,
-    Config, PriceInfo,
+    assert_not_paused, read_config, read_feeder, read_pause_status, read_price, read_prices,
+    set_paused, store_config, store_feeder, store_price, Config, Operation, PriceInfo,
 };
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::Empty;
 use cosmwasm_std::{
     attr, to_json_binary, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
 };
+use msgs::{assert_can_migrate, migrate_attributes};
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
@@ -21,11 +25,16 @@ pub fn instantiate(
     _info: MessageInfo,
     msg: InstantiateMsg,
 ) -> StdResult<Response> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&msg.owner))?;
     store_config(
         deps.storage,
         &Config {
-            owner: deps.api.addr_validate(&msg.owner)?,
             base_asset: msg.base_asset,
+            guardian: msg
+                .guardian
+                .map(|addr| deps.api.addr_validate(&addr))
+                .transpose()?,
         },
     )?;
 
@@ -40,43 +49,81 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::UpdateConfig { owner } => update_config(deps, info, owner),
+        ExecuteMsg::UpdateConfig { guardian } => update_config(deps, info, guardian),
         ExecuteMsg::RegisterFeeder { asset, feeder } => register_feeder(deps, info, asset, feeder),
         ExecuteMsg::UpdateFeeder { asset, feeder } => register_feeder(deps, info, asset, feeder),
         ExecuteMsg::FeedPrice { prices } => feed_prices(deps, env, info, prices),
+        ExecuteMsg::RemoveGuardian {} => remove_guardian(deps, info),
+        ExecuteMsg::Pause { operations } => pause(deps, info, operations),
+        ExecuteMsg::Unpause { operations } => unpause(deps, info, operations),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
     }
 }
 
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
-    owner: Option<String>,
+    guardian: Option<String>,
 ) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
     let mut config: Config = read_config(deps.storage)?;
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
     let mut res = Response::new().add_attribute("action", "update_config");
 
-    if let Some(owner) = owner {
-        config.owner = deps.api.addr_validate(&owner)?;
-        res = res.add_attribute("owner", owner);
+    if let Some(guardian) = guardian {
+        config.guardian = Some(deps.api.addr_validate(&guardian)?);
+        res = res.add_attribute("guardian", guardian);
     }
 
     store_config(deps.storage, &config)?;
     Ok(res)
 }
 
-pub fn register_feeder(
+pub fn remove_guardian(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = read_config(deps.storage)?;
+    config.guardian = None;
+    store_config(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "remove_guardian"))
+}
+
+pub fn pause(
     deps: DepsMut,
     info: MessageInfo,
-    asset: String,
-    feeder: String,
+    operations: Vec<Operation>,
 ) -> Result<Response, ContractError> {
     let config: Config = read_config(deps.storage)?;
-    if info.sender != config.owner {
+    if !cw_ownable::is_owner(deps.storage, &info.sender)?
+        && Some(&info.sender) != config.guardian.as_ref()
+    {
         return Err(ContractError::Unauthorized {});
     }
+    set_paused(deps.storage, &operations, true)?;
+
+    Ok(Response::new().add_attribute("action", "pause"))
+}
+
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    set_paused(deps.storage, &operations, false)?;
+
+    Ok(Response::new().add_attribute("action", "unpause"))
+}
+
+pub fn register_feeder(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: String,
+    feeder: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
 
     // We don't allow storing a new feeder for a contract
     if read_feeder(deps.storage, &asset).is_ok() {
@@ -98,10 +145,7 @@ pub fn update_feeder(
     asset: String,
     feeder: String,
 ) -> Result<Response, ContractError> {
-    let config: Config = read_config(deps.storage)?;
-    if info.sender != config.owner {
-        return Err(ContractError::Unauthorized {});
-    }
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
 
     // We don't allow storing a new feeder if it was not registered first
     if read_feeder(deps.storage, &asset).is_err() {
@@ -123,6 +167,8 @@ pub fn feed_prices(
     info: MessageInfo,
     prices: Vec<(String, Decimal256)>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Feed)?;
+
     let mut attributes = vec![attr("action", "feed_prices")];
     let sender_raw = deps.api.addr_validate(info.sender.as_str())?;
     for price in prices {
@@ -164,14 +210,16 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::Prices { start_after, limit } => {
             to_json_binary(&query_prices(deps, start_after, limit)?)
         }
+        QueryMsg::PauseStatus {} => to_json_binary(&read_pause_status(deps.storage)?),
+        QueryMsg::Ownership {} => to_json_binary(&cw_ownable::get_ownership(deps.storage)?),
     }
 }
 
 fn query_config(deps: Deps) -> StdResult<ConfigResponse> {
     let state = read_config(deps.storage)?;
     let resp = ConfigResponse {
-        owner: state.owner.to_string(),
         base_asset: state.base_asset,
+        guardian: state.guardian.map(|g| g.to_string()),
     };
 
     Ok(resp)
@@ -223,6 +271,12 @@ fn query_prices(
     Ok(PricesResponse { prices })
 }
 
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
-    Ok(Response::default())
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    migrate_storage(deps.branch(), from_version.as_ref())?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION)))
 }
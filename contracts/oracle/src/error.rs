@@ -1,6 +1,8 @@
 use cosmwasm_std::{OverflowError, StdError};
 use thiserror::Error;
 
+use crate::state::Operation;
+
 #[derive(Error, Debug, PartialEq)]
 pub enum ContractError {
     #[error("{0}")]
@@ -9,6 +11,9 @@ pub enum ContractError {
     #[error("{0}")]
     OverflowError(#[from] OverflowError),
 
+    #[error("{0}")]
+    Ownership(#[from] cw_ownable::OwnershipError),
+
     #[error("Unauthorized")]
     Unauthorized {},
 
@@ -20,4 +25,10 @@ pub enum ContractError {
 
     #[error("You can't provide a price equals 0")]
     PriceCantBeZero {},
+
+    #[error("{operation:?} is paused")]
+    Paused { operation: Operation },
+
+    #[error(transparent)]
+    Migrate(#[from] msgs::MigrateError),
 }
@@ -2,6 +2,7 @@ pub mod contract;
 pub mod error;
 #[cfg(feature = "interface")]
 pub mod interface;
+pub mod migrate;
 pub mod msg;
 pub mod state;
 
@@ -0,0 +1,37 @@
+use cosmwasm_std::{Addr, DepsMut};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::error::ContractError;
+use crate::state::{read_config, store_config};
+
+/// Config layout used before cw2 versioning, the owner was stored in the config
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct LegacyConfig {
+    pub owner: Addr,
+}
+
+const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// Transforms the storage written by `from` into the current layout.
+/// `from` is `None` for contracts instantiated before cw2 versioning
+pub fn migrate_storage(deps: DepsMut, from: Option<&Version>) -> Result<(), ContractError> {
+    if from.is_none() {
+        migrate_legacy_config(deps)?;
+    }
+    Ok(())
+}
+
+/// Moves the owner from the config to cw-ownable
+fn migrate_legacy_config(deps: DepsMut) -> Result<(), ContractError> {
+    let legacy = LEGACY_CONFIG.load(deps.storage)?;
+
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(legacy.owner.as_str()))?;
+    // Rewriting the config drops the legacy owner field
+    let config = read_config(deps.storage)?;
+    store_config(deps.storage, &config)?;
+
+    Ok(())
+}
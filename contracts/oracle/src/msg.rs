@@ -3,19 +3,24 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use cosmwasm_std::Decimal256;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
+
+use crate::state::{Operation, PauseStatus};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
     pub owner: String,
     pub base_asset: String,
+    pub guardian: Option<String>,
 }
 
+#[cw_ownable_execute]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
     UpdateConfig {
-        owner: Option<String>,
+        guardian: Option<String>,
     },
     RegisterFeeder {
         asset: String,
@@ -25,11 +30,26 @@ pub enum ExecuteMsg {
         asset: String,
         feeder: String,
     },
+    /// Prices of whole tokens in the base asset, whatever their decimals
     FeedPrice {
         prices: Vec<(String, Decimal256)>, // (asset, price)
     },
+    /// Removes the guardian, only the owner can pause afterwards
+    RemoveGuardian {},
+    /// Can be called by the guardian or the owner
+    Pause {
+        operations: Vec<Operation>,
+    },
+    /// Can only be called by the owner
+    Unpause {
+        operations: Vec<Operation>,
+    },
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct MigrateMsg {}
+
+#[cw_ownable_query]
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
 #[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
@@ -46,13 +66,15 @@ pub enum QueryMsg {
         start_after: Option<String>,
         limit: Option<u32>,
     },
+    #[returns(PauseStatus)]
+    PauseStatus {},
 }
 
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct ConfigResponse {
-    pub owner: String,
     pub base_asset: String,
+    pub guardian: Option<String>,
 }
 
 // We define a custom struct for each query response
@@ -65,6 +87,7 @@ pub struct FeederResponse {
 // We define a custom struct for each query response
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PriceResponse {
+    /// Whole quote tokens for one whole base token
     pub rate: Decimal256,
     pub last_updated_base: u64,
     pub last_updated_quote: u64,
@@ -1,14 +1,15 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::error::ContractError;
 use crate::msg::PricesResponseElem;
 use cosmwasm_std::{Addr, Decimal256, Order, StdError, StdResult, Storage};
 use cw_storage_plus::{Bound, Item, Map};
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
-    pub owner: Addr,
     pub base_asset: String,
+    pub guardian: Option<Addr>,
 }
 pub const CONFIG: Item<Config> = Item::new("config");
 
@@ -20,6 +21,60 @@ pub fn read_config(storage: &dyn Storage) -> StdResult<Config> {
     CONFIG.load(storage)
 }
 
+/// Operations the guardian can pause
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Operation {
+    Feed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct PauseStatus {
+    pub feed: bool,
+}
+
+impl PauseStatus {
+    pub fn is_paused(&self, operation: &Operation) -> bool {
+        match operation {
+            Operation::Feed => self.feed,
+        }
+    }
+
+    pub fn set(&mut self, operation: &Operation, paused: bool) {
+        match operation {
+            Operation::Feed => self.feed = paused,
+        }
+    }
+}
+
+pub const PAUSED: Item<PauseStatus> = Item::new("paused");
+
+pub fn store_pause_status(storage: &mut dyn Storage, status: &PauseStatus) -> StdResult<()> {
+    PAUSED.save(storage, status)
+}
+
+pub fn read_pause_status(storage: &dyn Storage) -> StdResult<PauseStatus> {
+    Ok(PAUSED.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn set_paused(
+    storage: &mut dyn Storage,
+    operations: &[Operation],
+    paused: bool,
+) -> Result<(), ContractError> {
+    let mut status = read_pause_status(storage)?;
+    operations.iter().for_each(|op| status.set(op, paused));
+    store_pause_status(storage, &status)?;
+    Ok(())
+}
+
+pub fn assert_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    if read_pause_status(storage)?.is_paused(&operation) {
+        return Err(ContractError::Paused { operation });
+    }
+    Ok(())
+}
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct PriceInfo {
     pub price: Decimal256,
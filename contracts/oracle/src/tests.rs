@@ -1,11 +1,14 @@
-use crate::contract::{execute, instantiate, query};
+use crate::contract::{execute, instantiate, migrate, query, CONTRACT_NAME, CONTRACT_VERSION};
 use crate::error::ContractError;
 use crate::msg::{
-    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, PriceResponse, PricesResponse,
-    PricesResponseElem, QueryMsg,
+    ConfigResponse, ExecuteMsg, FeederResponse, InstantiateMsg, MigrateMsg, PriceResponse,
+    PricesResponse, PricesResponseElem, QueryMsg,
 };
+use crate::state::{Operation, PauseStatus};
 use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-use cosmwasm_std::{from_json, Decimal256};
+use cosmwasm_std::{from_json, Decimal256, Storage};
+use cw_ownable::{Action, Ownership, OwnershipError};
+use msgs::MigrateError;
 use std::str::FromStr;
 
 #[test]
@@ -15,6 +18,7 @@ fn proper_initialization() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        guardian: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -26,8 +30,11 @@ fn proper_initialization() {
     // it worked, let's query the state
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let value: ConfigResponse = from_json(res).unwrap();
-    assert_eq!("owner0000", value.owner.as_str());
     assert_eq!("base0000", &value.base_asset);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    let value: Ownership<String> = from_json(res).unwrap();
+    assert_eq!(Some("owner0000".to_string()), value.owner);
 }
 
 #[test]
@@ -37,15 +44,16 @@ fn update_config() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        guardian: None,
     };
 
     let info = mock_info("addr0000", &[]);
     let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-    // update owner
+    // update guardian
     let info = mock_info("owner0000", &[]);
     let msg = ExecuteMsg::UpdateConfig {
-        owner: Some("owner0001".to_string()),
+        guardian: Some("guardian0000".to_string()),
     };
 
     let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
@@ -54,16 +62,70 @@ fn update_config() {
     // it worked, let's query the state
     let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
     let value: ConfigResponse = from_json(res).unwrap();
-    assert_eq!("owner0001", value.owner.as_str());
+    assert_eq!(Some("guardian0000".to_string()), value.guardian);
     assert_eq!("base0000", &value.base_asset);
 
     // Unauthorized err
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::UpdateConfig { guardian: None };
+
+    let res = execute(deps.as_mut(), mock_env(), info, msg);
+    match res {
+        Err(ContractError::Ownership(OwnershipError::NotOwner)) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+}
+
+#[test]
+fn transfer_ownership() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        guardian: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // propose a new owner
     let info = mock_info("owner0000", &[]);
-    let msg = ExecuteMsg::UpdateConfig { owner: None };
+    let msg = ExecuteMsg::UpdateOwnership(Action::TransferOwnership {
+        new_owner: "owner0001".to_string(),
+        expiry: None,
+    });
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // the current owner keeps its rights until the transfer is accepted
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    let value: Ownership<String> = from_json(res).unwrap();
+    assert_eq!(Some("owner0000".to_string()), value.owner);
+    assert_eq!(Some("owner0001".to_string()), value.pending_owner);
+
+    // only the pending owner can accept
+    let info = mock_info("addr0000", &[]);
+    let msg = ExecuteMsg::UpdateOwnership(Action::AcceptOwnership);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::Ownership(OwnershipError::NotPendingOwner)) => (),
+        _ => panic!("Must return not pending owner error"),
+    }
+
+    let info = mock_info("owner0001", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    let value: Ownership<String> = from_json(res).unwrap();
+    assert_eq!(Some("owner0001".to_string()), value.owner);
+    assert_eq!(None, value.pending_owner);
 
+    // the previous owner lost its rights
+    let info = mock_info("owner0000", &[]);
+    let msg = ExecuteMsg::UpdateConfig { guardian: None };
     let res = execute(deps.as_mut(), mock_env(), info, msg);
     match res {
-        Err(ContractError::Unauthorized {}) => (),
+        Err(ContractError::Ownership(OwnershipError::NotOwner)) => (),
         _ => panic!("Must return unauthorized error"),
     }
 }
@@ -75,6 +137,7 @@ fn register_feeder() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        guardian: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -88,7 +151,7 @@ fn register_feeder() {
 
     let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
     match res {
-        Err(ContractError::Unauthorized {}) => (),
+        Err(ContractError::Ownership(OwnershipError::NotOwner)) => (),
         _ => panic!("DO NOT ENTER HERE"),
     }
 
@@ -121,6 +184,7 @@ fn feed_price() {
     let msg = InstantiateMsg {
         owner: "owner0000".to_string(),
         base_asset: "base0000".to_string(),
+        guardian: None,
     };
 
     let info = mock_info("addr0000", &[]);
@@ -233,3 +297,156 @@ fn feed_price() {
         _ => panic!("Must return unauthorized error"),
     }
 }
+
+#[test]
+fn pause_feed() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        guardian: Some("guardian0000".to_string()),
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let msg = ExecuteMsg::RegisterFeeder {
+        asset: "mAAPL".to_string(),
+        feeder: "feeder0000".to_string(),
+    };
+    let info = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    // Only the guardian or the owner can pause
+    let msg = ExecuteMsg::Pause {
+        operations: vec![Operation::Feed],
+    };
+    let info = mock_info("addr0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info, msg.clone());
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let info = mock_info("guardian0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::PauseStatus {}).unwrap();
+    let value: PauseStatus = from_json(res).unwrap();
+    assert_eq!(value, PauseStatus { feed: true });
+
+    let feed_msg = ExecuteMsg::FeedPrice {
+        prices: vec![("mAAPL".to_string(), Decimal256::from_str("1.2").unwrap())],
+    };
+    let info = mock_info("feeder0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), info.clone(), feed_msg.clone());
+    assert_eq!(
+        res,
+        Err(ContractError::Paused {
+            operation: Operation::Feed
+        })
+    );
+
+    // Only the owner can unpause
+    let msg = ExecuteMsg::Unpause {
+        operations: vec![Operation::Feed],
+    };
+    let guardian = mock_info("guardian0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), guardian, msg.clone());
+    match res {
+        Err(ContractError::Ownership(OwnershipError::NotOwner)) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+
+    let owner = mock_info("owner0000", &[]);
+    let _res = execute(deps.as_mut(), mock_env(), owner.clone(), msg).unwrap();
+    let _res = execute(deps.as_mut(), mock_env(), info, feed_msg).unwrap();
+
+    // Once removed, the guardian can't pause anymore
+    let _res = execute(
+        deps.as_mut(),
+        mock_env(),
+        owner,
+        ExecuteMsg::RemoveGuardian {},
+    )
+    .unwrap();
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let value: ConfigResponse = from_json(res).unwrap();
+    assert_eq!(value.guardian, None);
+
+    let msg = ExecuteMsg::Pause {
+        operations: vec![Operation::Feed],
+    };
+    let guardian = mock_info("guardian0000", &[]);
+    let res = execute(deps.as_mut(), mock_env(), guardian, msg);
+    match res {
+        Err(ContractError::Unauthorized {}) => (),
+        _ => panic!("Must return unauthorized error"),
+    }
+}
+
+#[test]
+fn migrate_versions() {
+    let mut deps = mock_dependencies();
+
+    let msg = InstantiateMsg {
+        owner: "owner0000".to_string(),
+        base_asset: "base0000".to_string(),
+        guardian: None,
+    };
+
+    let info = mock_info("addr0000", &[]);
+    let _res = instantiate(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(CONTRACT_NAME, version.contract);
+    assert_eq!(CONTRACT_VERSION, version.version);
+
+    // Migrating to the same version is allowed
+    let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    // Downgrades are refused
+    cw2::set_contract_version(deps.as_mut().storage, CONTRACT_NAME, "99.0.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    assert_eq!(
+        res,
+        Err(ContractError::Migrate(MigrateError::CannotDowngrade {
+            stored: "99.0.0".to_string(),
+            new: CONTRACT_VERSION.to_string(),
+        }))
+    );
+
+    // Other contracts are refused
+    cw2::set_contract_version(deps.as_mut().storage, "crates.io:other", "0.0.0").unwrap();
+    let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {});
+    match res {
+        Err(ContractError::Migrate(MigrateError::WrongContract { .. })) => (),
+        _ => panic!("Must return wrong contract error"),
+    }
+}
+
+#[test]
+fn migrate_legacy_owner() {
+    let mut deps = mock_dependencies();
+
+    // State written before cw2 versioning, the owner lives in the config
+    deps.storage.set(
+        b"config",
+        br#"{"owner":"owner0000","base_asset":"base0000"}"#,
+    );
+
+    let _res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Ownership {}).unwrap();
+    let value: Ownership<String> = from_json(res).unwrap();
+    assert_eq!(Some("owner0000".to_string()), value.owner);
+
+    let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+    let value: ConfigResponse = from_json(res).unwrap();
+    assert_eq!("base0000", &value.base_asset);
+    assert_eq!(None, value.guardian);
+
+    let version = cw2::get_contract_version(deps.as_ref().storage).unwrap();
+    assert_eq!(CONTRACT_VERSION, version.version);
+}
@@ -1,18 +1,28 @@
 #[cfg(not(feature = "library"))]
 use cosmwasm_std::entry_point;
-use cosmwasm_std::Empty;
-use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdResult};
+use cosmwasm_std::{Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult};
 use cw20_base::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use cw20_base::ContractError;
+use msgs::{assert_can_migrate, migrate_attributes};
+
+use crate::msg::MigrateMsg;
+
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+/// Version info written by cw20-base, found on tokens instantiated before versioning
+const CW20_BASE_CONTRACT_NAME: &str = "crates.io:cw20-base";
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
-    cw20_base::contract::instantiate(deps, env, info, msg)
+    let res = cw20_base::contract::instantiate(deps.branch(), env, info, msg)?;
+    // cw20-base writes its own version info, we overwrite it
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    Ok(res)
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
@@ -35,6 +45,16 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     cw20_base::contract::query(deps, env, msg)
 }
 
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: Empty) -> StdResult<Response> {
-    Ok(Response::default())
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = cw2::get_contract_version(deps.storage)?;
+    let from_version = if stored.contract == CW20_BASE_CONTRACT_NAME {
+        None
+    } else {
+        assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION).map_err(StdError::from)?
+    };
+
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION)))
 }
@@ -1,8 +1,9 @@
+use crate::msg::MigrateMsg;
 use cosmwasm_std::Empty;
 use cw20_base::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
 use cw_orch::{interface, prelude::*};
 
-#[interface(InstantiateMsg, ExecuteMsg, QueryMsg, Empty)]
+#[interface(InstantiateMsg, ExecuteMsg, QueryMsg, MigrateMsg)]
 pub struct StakingToken;
 
 impl<Chain: CwEnv> Uploadable for StakingToken<Chain> {
@@ -14,10 +15,13 @@ impl<Chain: CwEnv> Uploadable for StakingToken<Chain> {
     }
     /// Returns a CosmWasm contract wrapper
     fn wrapper(&self) -> Box<dyn MockContract<Empty>> {
-        Box::new(ContractWrapper::new_with_empty(
-            crate::contract::execute,
-            crate::contract::instantiate,
-            crate::contract::query,
-        ))
+        Box::new(
+            ContractWrapper::new_with_empty(
+                crate::contract::execute,
+                crate::contract::instantiate,
+                crate::contract::query,
+            )
+            .with_migrate(crate::contract::migrate),
+        )
     }
 }
@@ -1,3 +1,4 @@
 pub mod contract;
 #[cfg(feature = "interface")]
 pub mod interface;
+pub mod msg;
@@ -0,0 +1,5 @@
+use cosmwasm_schema::cw_serde;
+
+/// Message type for `migrate` entry_point
+#[cw_serde]
+pub struct MigrateMsg {}
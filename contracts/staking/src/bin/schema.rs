@@ -1,6 +1,6 @@
 use cosmwasm_schema::write_api;
 
-use staking_contract::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
+use staking_contract::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 
 fn main() {
     write_api! {
@@ -8,5 +8,6 @@ fn main() {
         migrate: MigrateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        sudo: SudoMsg,
     }
 }
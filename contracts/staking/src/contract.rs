@@ -5,23 +5,29 @@ use cosmwasm_std::{
     attr, to_json_binary, Addr, Binary, Decimal256, Deps, DepsMut, Env, MessageInfo, Reply,
     Response, StdError, Timestamp,
 };
+use msgs::{assert_can_migrate, migrate_attributes};
 use protobuf::Message;
 
 use crate::error::ContractError;
 use crate::execute::{
-    execute_claim, execute_stake, instantiate_staking_token, mint, rebase, unstake,
+    begin_blocker, burn, execute_claim, execute_stake, instantiate_staking_token, mint, rebase,
+    unstake,
 };
-use crate::helpers::{create_denom_msg, mint_msgs};
-use crate::msg::{BondContractsElem, ExecuteMsg, InstantiateMsg, QueryMsg};
+use crate::helpers::{create_denom_msg, mint_msgs, sweep_msgs};
+use crate::migrate::migrate_storage;
+use crate::msg::{BondContractsElem, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, SudoMsg};
 use crate::query::{
     base_denom, query_config, query_current_staking_points, query_exchange_rate,
     query_raw_staking_points,
 };
 use crate::state::{
-    bond_contracts, BondContractInfo, Config, EpochState, BASE_TOKEN_DENOM, BOND_CONTRACT_INFO,
-    CONFIG, EPOCH_STATE,
+    bond_contracts, pause_status, set_paused, BondContractInfo, Config, EpochState, Operation,
+    BASE_TOKEN_DENOM, BOND_CONTRACT_INFO, CONFIG, EPOCH_STATE, HOOKS,
 };
 
+pub const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+pub const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
@@ -29,18 +35,21 @@ pub fn instantiate(
     info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    let owner = msg.owner.unwrap_or_else(|| info.sender.to_string());
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+
     let config = Config {
-        admin: msg
-            .admin
-            .map(|addr| deps.api.addr_validate(&addr))
-            .transpose()?
-            .unwrap_or(info.sender),
         epoch_length: msg.epoch_length,
         epoch_apr: msg.epoch_apr,
         staking_denom_address: None,
         next_epoch_apr: None,
         warmup_address: None,
         warmup_length: msg.warmup_length,
+        guardian: msg
+            .guardian
+            .map(|addr| deps.api.addr_validate(&addr))
+            .transpose()?,
     };
 
     let state = EpochState {
@@ -82,20 +91,21 @@ pub fn execute(
         ExecuteMsg::Unstake { to, amount } => unstake(deps, env, info, to, amount),
         ExecuteMsg::Rebase {} => rebase(deps, env, info),
         ExecuteMsg::Mint { to, amount } => mint(deps, env, info, to, amount),
+        ExecuteMsg::Burn {} => burn(env, info),
         ExecuteMsg::UpdateConfig {
-            admin,
             epoch_length,
             epoch_apr,
             add_bond,
             remove_bond,
+            guardian,
         } => update_config(
             deps,
             info,
-            admin,
             epoch_length,
             epoch_apr,
             add_bond,
             remove_bond,
+            guardian,
         ),
         ExecuteMsg::InstantiateContracts {
             staking_token_code_id,
@@ -111,6 +121,15 @@ pub fn execute(
             staking_name,
             cw1_code_id,
         ),
+        ExecuteMsg::AddHook { addr } => add_hook(deps, info, addr),
+        ExecuteMsg::RemoveHook { addr } => remove_hook(deps, info, addr),
+        ExecuteMsg::RemoveGuardian {} => remove_guardian(deps, info),
+        ExecuteMsg::Pause { operations } => pause(deps, info, operations),
+        ExecuteMsg::Unpause { operations } => unpause(deps, info, operations),
+        ExecuteMsg::UpdateOwnership(action) => {
+            let ownership = cw_ownable::update_ownership(deps, &env.block, &info.sender, action)?;
+            Ok(Response::new().add_attributes(ownership.into_attributes()))
+        }
     }
 }
 
@@ -128,10 +147,57 @@ pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> Result<Binary, ContractErro
         QueryMsg::RawStakingPoints { address } => {
             Ok(to_json_binary(&query_raw_staking_points(deps, address)?)?)
         }
+        QueryMsg::Hooks {} => Ok(to_json_binary(&HOOKS.query_hooks(deps)?)?),
+        QueryMsg::PauseStatus {} => Ok(to_json_binary(&pause_status(deps.storage)?)?),
+        QueryMsg::Ownership {} => Ok(to_json_binary(&cw_ownable::get_ownership(deps.storage)?)?),
     }
 }
+
+/// Handling governance calls, they bypass the owner and guardian checks
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn sudo(deps: DepsMut, env: Env, msg: SudoMsg) -> Result<Response, ContractError> {
+    match msg {
+        SudoMsg::UpdateOwner { owner } => {
+            let ownership = cw_ownable::initialize_owner(deps.storage, deps.api, Some(&owner))?;
+            Ok(Response::new()
+                .add_attribute("action", "sudo_update_owner")
+                .add_attributes(ownership.into_attributes()))
+        }
+        SudoMsg::Pause { operations } => {
+            set_paused(deps.storage, &operations, true)?;
+            Ok(Response::new().add_attribute("action", "sudo_pause"))
+        }
+        SudoMsg::Unpause { operations } => {
+            set_paused(deps.storage, &operations, false)?;
+            Ok(Response::new().add_attribute("action", "sudo_unpause"))
+        }
+        SudoMsg::SweepFunds { recipient } => {
+            let recipient = deps.api.addr_validate(&recipient)?;
+            Ok(Response::new()
+                .add_messages(sweep_msgs(deps.as_ref(), &env, &recipient)?)
+                .add_attribute("action", "sudo_sweep_funds")
+                .add_attribute("recipient", recipient))
+        }
+        SudoMsg::BeginBlocker {} => begin_blocker(deps, env),
+    }
+}
+
+/// Handling contract migration
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(mut deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let from_version = assert_can_migrate(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    migrate_storage(deps.branch(), from_version.as_ref())?;
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(migrate_attributes(from_version.as_ref(), CONTRACT_VERSION)))
+}
+
 pub const INSTANTIATE_STAKING_TOKEN_REPLY: u64 = 1;
 pub const INSTANTIATE_ADMIN_CONTRACT_REPLY: u64 = 2;
+pub const HOOK_REPLY: u64 = 3;
+/// Gas given to each hook notification, a hook running out of it is handled like any other failure
+pub const HOOK_GAS_LIMIT: u64 = 300_000;
 /// Handling contract replies
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, ContractError> {
@@ -174,6 +240,11 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
             })?;
             Ok(Response::new().add_attributes(vec![attr("warmup", warmup_addr)]))
         }
+        HOOK_REPLY => {
+            // A failing hook should never revert the staking operation, we only log the error
+            let err = reply.result.unwrap_err();
+            Ok(Response::new().add_attributes(vec![attr("hook_error", err)]))
+        }
         _ => Err(ContractError::InvalidReplyId {}),
     }
 }
@@ -181,19 +252,14 @@ pub fn reply(deps: DepsMut, _env: Env, reply: Reply) -> Result<Response, Contrac
 pub fn update_config(
     deps: DepsMut,
     info: MessageInfo,
-    admin: Option<String>,
     epoch_length: Option<u64>,
     epoch_apr: Option<Decimal256>,
     add_bond: Option<Vec<BondContractsElem>>,
     remove_bond: Option<Vec<String>>,
+    guardian: Option<String>,
 ) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
     let mut config = CONFIG.load(deps.storage)?;
-    if info.sender != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
-    if let Some(admin) = admin {
-        config.admin = deps.api.addr_validate(&admin)?;
-    }
     if let Some(epoch_length) = epoch_length {
         config.epoch_length = epoch_length;
     }
@@ -217,10 +283,67 @@ pub fn update_config(
             BOND_CONTRACT_INFO.remove(deps.storage, &deps.api.addr_validate(&bond)?);
         }
     }
+    if let Some(guardian) = guardian {
+        config.guardian = Some(deps.api.addr_validate(&guardian)?);
+    }
     CONFIG.save(deps.storage, &config)?;
     Ok(Response::new())
 }
 
+pub fn remove_guardian(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    let mut config = CONFIG.load(deps.storage)?;
+    config.guardian = None;
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attribute("action", "remove_guardian"))
+}
+
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if !cw_ownable::is_owner(deps.storage, &info.sender)?
+        && Some(&info.sender) != config.guardian.as_ref()
+    {
+        return Err(ContractError::Unauthorized {});
+    }
+    set_paused(deps.storage, &operations, true)?;
+
+    Ok(Response::new().add_attribute("action", "pause"))
+}
+
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    operations: Vec<Operation>,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    set_paused(deps.storage, &operations, false)?;
+
+    Ok(Response::new().add_attribute("action", "unpause"))
+}
+
+pub fn add_hook(deps: DepsMut, info: MessageInfo, addr: String) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    HOOKS.add_hook(deps.storage, deps.api.addr_validate(&addr)?)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "add_hook"), attr("hook", addr)]))
+}
+
+pub fn remove_hook(
+    deps: DepsMut,
+    info: MessageInfo,
+    addr: String,
+) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
+    HOOKS.remove_hook(deps.storage, deps.api.addr_validate(&addr)?)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "remove_hook"), attr("hook", addr)]))
+}
+
 #[cfg(test)]
 pub mod test {
     use cosmwasm_std::{coins, Decimal256};
@@ -231,11 +354,19 @@ pub mod test {
     use tests::tokenfactory::assert_cw20_balance;
 
     use cw_orch::injective_test_tube::injective_test_tube::Account;
+    use cw_ownable::Action;
     use cw_plus_interface::cw1_whitelist::Cw1Whitelist;
+    use cw_utils::Expiration;
+    use mock_hook::interface::MockHook;
+    use mock_hook::msg::QueryMsgFns as _;
     use staking_contract::interface::Staking;
+    use staking_contract::msg::ExecuteMsg;
     use staking_contract::msg::ExecuteMsgFns;
     use staking_contract::msg::InstantiateMsg;
     use staking_contract::msg::QueryMsgFns;
+    use staking_contract::msg::SudoMsg;
+    use staking_contract::state::{Operation, PauseStatus};
+    use tests::sudo::wasm_sudo;
     use tests::tokenfactory::assert_balance;
     pub const AMOUNT_TO_CREATE_DENOM: u128 = 10_000_000_000_000_000_000u128;
     pub const FUNDS_MULTIPLIER: u128 = 100_000;
@@ -257,12 +388,13 @@ pub mod test {
 
         contract.instantiate(
             &InstantiateMsg {
-                admin: None,
+                owner: None,
                 epoch_apr: Decimal256::from_str("0.1")?,
                 first_epoch_time: block_info.time.seconds() + 1,
                 epoch_length: EPOCH_LENGTH,
                 initial_balances: vec![(chain.sender().to_string(), 1_000_000u128.into())],
                 warmup_length: WARMUP_LENGTH,
+                guardian: None,
             },
             None,
             Some(&coins(AMOUNT_TO_CREATE_DENOM * 2, "inj")),
@@ -329,6 +461,23 @@ pub mod test {
         Ok(())
     }
 
+    #[test]
+    pub fn burn_works() -> anyhow::Result<()> {
+        let contract = init()?;
+        let chain = contract.get_chain().clone();
+        let ohm_denom = contract.config()?.ohm_denom;
+
+        contract.burn(&coins(10_000, ohm_denom.clone()))?;
+        assert_balance(
+            chain.clone(),
+            ohm_denom,
+            990_000,
+            chain.sender().to_string(),
+        )?;
+
+        Ok(())
+    }
+
     #[test]
     pub fn warmup_works() -> anyhow::Result<()> {
         let contract: Staking<InjectiveTestTube> = init()?;
@@ -664,4 +813,275 @@ pub mod test {
 
         Ok(())
     }
+
+    #[test]
+    pub fn hooks_are_isolated() -> anyhow::Result<()> {
+        let contract: Staking<InjectiveTestTube> = init()?;
+        let mut chain = contract.get_chain().clone();
+        let hook = chain.init_account(vec![])?;
+
+        // Only the owner can register hooks
+        contract
+            .call_as(&hook)
+            .add_hook(hook.address().to_string())
+            .unwrap_err();
+
+        // This hook is not a contract, every notification will fail
+        contract.add_hook(hook.address().to_string())?;
+        assert_eq!(contract.hooks()?.hooks, vec![hook.address().to_string()]);
+
+        let sohm_address = contract.config()?.sohm_address;
+        stake_and_claim(&contract, 10_000, None)?;
+        unstake(&contract, 10_000, None)?;
+
+        assert_cw20_balance(chain.clone(), sohm_address, 0, chain.sender().to_string())?;
+
+        contract.remove_hook(hook.address().to_string())?;
+        assert!(contract.hooks()?.hooks.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn hooks_are_gas_limited() -> anyhow::Result<()> {
+        let contract: Staking<InjectiveTestTube> = init()?;
+        let chain = contract.get_chain().clone();
+
+        // This hook burns all the gas it is given
+        let hook = MockHook::new("hook", chain.clone());
+        hook.upload()?;
+        hook.instantiate(&mock_hook::msg::InstantiateMsg {}, None, None)?;
+        contract.add_hook(hook.address()?.to_string())?;
+
+        let sohm_address = contract.config()?.sohm_address;
+        stake_and_claim(&contract, 10_000, None)?;
+        unstake(&contract, 10_000, None)?;
+
+        assert_cw20_balance(chain.clone(), sohm_address, 0, chain.sender().to_string())?;
+        // No notification went through
+        assert_eq!(hook.notifications()?, 0);
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn guardian_pauses_operations() -> anyhow::Result<()> {
+        let contract: Staking<InjectiveTestTube> = init()?;
+        let mut chain = contract.get_chain().clone();
+        let guardian = chain.init_account(vec![])?;
+
+        contract.update_config(None, None, None, Some(guardian.address().to_string()), None)?;
+        assert_eq!(
+            contract.config()?.guardian,
+            Some(guardian.address().to_string())
+        );
+
+        contract
+            .call_as(&guardian)
+            .pause(vec![Operation::Stake, Operation::Unstake])?;
+        assert_eq!(
+            contract.pause_status()?,
+            PauseStatus {
+                stake: true,
+                claim: false,
+                unstake: true,
+                rebase: false,
+            }
+        );
+
+        let err = contract
+            .stake(
+                chain.sender().to_string(),
+                &coins(10_000, contract.config()?.ohm_denom),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Stake is paused"));
+
+        // Only the owner can unpause
+        contract
+            .call_as(&guardian)
+            .unpause(vec![Operation::Stake])
+            .unwrap_err();
+        contract.unpause(vec![Operation::Stake])?;
+
+        stake_and_claim(&contract, 10_000, None)?;
+        unstake(&contract, 10_000, None).unwrap_err();
+
+        // Once removed, the guardian can't pause anymore
+        contract.remove_guardian()?;
+        assert_eq!(contract.config()?.guardian, None);
+        contract
+            .call_as(&guardian)
+            .pause(vec![Operation::Stake])
+            .unwrap_err();
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn ownership_is_transferred_in_two_steps() -> anyhow::Result<()> {
+        let contract: Staking<InjectiveTestTube> = init()?;
+        let mut chain = contract.get_chain().clone();
+        let new_owner = chain.init_account(vec![])?;
+        let expiry = Expiration::AtTime(chain.block_info()?.time.plus_seconds(1_000));
+
+        contract.execute(
+            &ExecuteMsg::UpdateOwnership(Action::TransferOwnership {
+                new_owner: new_owner.address().to_string(),
+                expiry: Some(expiry),
+            }),
+            None,
+        )?;
+
+        // Nothing changes until the transfer is accepted
+        let ownership = contract.ownership()?;
+        assert_eq!(ownership.owner, Some(chain.sender().to_string()));
+        assert_eq!(
+            ownership.pending_owner,
+            Some(new_owner.address().to_string())
+        );
+        assert_eq!(ownership.pending_expiry, Some(expiry));
+
+        contract
+            .call_as(&new_owner)
+            .execute(&ExecuteMsg::UpdateOwnership(Action::AcceptOwnership), None)?;
+
+        let ownership = contract.ownership()?;
+        assert_eq!(ownership.owner, Some(new_owner.address().to_string()));
+        assert_eq!(ownership.pending_owner, None);
+
+        // The previous owner lost its rights
+        contract
+            .update_config(None, Some(EPOCH_LENGTH * 2), None, None, None)
+            .unwrap_err();
+        contract.call_as(&new_owner).update_config(
+            None,
+            Some(EPOCH_LENGTH * 2),
+            None,
+            None,
+            None,
+        )?;
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn expired_ownership_transfer_fails() -> anyhow::Result<()> {
+        let contract: Staking<InjectiveTestTube> = init()?;
+        let mut chain = contract.get_chain().clone();
+        let new_owner = chain.init_account(vec![])?;
+
+        contract.execute(
+            &ExecuteMsg::UpdateOwnership(Action::TransferOwnership {
+                new_owner: new_owner.address().to_string(),
+                expiry: Some(Expiration::AtTime(
+                    chain.block_info()?.time.plus_seconds(100),
+                )),
+            }),
+            None,
+        )?;
+        chain.wait_seconds(200)?;
+
+        let err = contract
+            .call_as(&new_owner)
+            .execute(&ExecuteMsg::UpdateOwnership(Action::AcceptOwnership), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("expired"));
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn governance_sudo_controls() -> anyhow::Result<()> {
+        let contract: Staking<InjectiveTestTube> = init()?;
+        let mut chain = contract.get_chain().clone();
+        let new_owner = chain.init_account(vec![])?;
+        let recovery = chain.init_account(vec![])?;
+        let staking = contract.address()?;
+
+        wasm_sudo(
+            &chain,
+            &staking,
+            &SudoMsg::UpdateOwner {
+                owner: new_owner.address().to_string(),
+            },
+        )?;
+        assert_eq!(
+            contract.ownership()?.owner,
+            Some(Addr::unchecked(new_owner.address()))
+        );
+
+        wasm_sudo(
+            &chain,
+            &staking,
+            &SudoMsg::Pause {
+                operations: vec![Operation::Stake],
+            },
+        )?;
+        let err = contract
+            .stake(
+                chain.sender().to_string(),
+                &coins(10_000, contract.config()?.ohm_denom),
+            )
+            .unwrap_err();
+        assert!(err.to_string().contains("Stake is paused"));
+
+        wasm_sudo(
+            &chain,
+            &staking,
+            &SudoMsg::Unpause {
+                operations: vec![Operation::Stake],
+            },
+        )?;
+        stake_and_claim(&contract, 10_000, None)?;
+
+        wasm_sudo(
+            &chain,
+            &staking,
+            &SudoMsg::SweepFunds {
+                recipient: recovery.address().to_string(),
+            },
+        )?;
+        assert_balance(
+            chain.clone(),
+            contract.config()?.ohm_denom,
+            10_000,
+            recovery.address().to_string(),
+        )?;
+        assert!(chain.balance(staking, None)?.is_empty());
+
+        Ok(())
+    }
+
+    #[test]
+    pub fn begin_blocker_rebases() -> anyhow::Result<()> {
+        let contract: Staking<InjectiveTestTube> = init()?;
+        let chain = contract.get_chain().clone();
+        let staking = contract.address()?;
+
+        // The warmup outlasts the first epoch
+        stake_and_claim(&contract, 500_000, None)?;
+        let epoch_number = contract.epoch_state()?.epoch_number;
+
+        wasm_sudo(&chain, &staking, &SudoMsg::BeginBlocker {})?;
+        assert_eq!(contract.epoch_state()?.epoch_number, epoch_number + 1);
+        assert_balance(
+            chain.clone(),
+            contract.config()?.ohm_denom,
+            550_000,
+            staking.to_string(),
+        )?;
+
+        // A paused rebase doesn't make the begin blocker fail
+        contract.pause(vec![Operation::Rebase])?;
+        chain.wait_seconds(EPOCH_LENGTH)?;
+        wasm_sudo(&chain, &staking, &SudoMsg::BeginBlocker {})?;
+        assert_eq!(contract.epoch_state()?.epoch_number, epoch_number + 1);
+
+        contract.unpause(vec![Operation::Rebase])?;
+        wasm_sudo(&chain, &staking, &SudoMsg::BeginBlocker {})?;
+        assert_eq!(contract.epoch_state()?.epoch_number, epoch_number + 2);
+
+        Ok(())
+    }
 }
@@ -1,6 +1,8 @@
 use cosmwasm_std::{Addr, StdError};
 use thiserror::Error;
 
+use crate::state::Operation;
+
 #[derive(Error, Debug)]
 pub enum ContractError {
     #[error("{0}")]
@@ -21,10 +23,22 @@ pub enum ContractError {
     #[error("You need to send exactly one coin with this function with denom {0}")]
     ReceiveOneCoin(String),
 
+    #[error(transparent)]
+    HookError(#[from] cw_controllers::HookError),
+
+    #[error(transparent)]
+    Ownership(#[from] cw_ownable::OwnershipError),
+
     #[error(transparent)]
     ConversionOverflowError(#[from] cosmwasm_std::ConversionOverflowError),
     // Add any other custom errors you like here.
     // Look at https://docs.rs/thiserror/1.0.21/thiserror/ for details.
     #[error("Invalid reply id on rply endpoint")]
     InvalidReplyId {},
+
+    #[error("{operation:?} is paused")]
+    Paused { operation: Operation },
+
+    #[error(transparent)]
+    Migrate(#[from] msgs::MigrateError),
 }
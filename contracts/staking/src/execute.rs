@@ -8,11 +8,14 @@ use injective_std::types::injective::tokenfactory::v1beta1::MsgMint;
 
 use crate::{
     contract::{INSTANTIATE_ADMIN_CONTRACT_REPLY, INSTANTIATE_STAKING_TOKEN_REPLY},
-    helpers::{deposit_one_coin, mint_msgs},
-    query::{base_denom, current_exchange_rate, staking_token_addr, token_balance},
+    helpers::{burn_msg, deposit_one_coin, hook_msgs, mint_msgs},
+    msg::{StakeAction, StakeChangedHookMsg},
+    query::{
+        base_denom, current_exchange_rate, staking_token_addr, staking_token_balance, token_balance,
+    },
     state::{
-        update_staking_points, StakingPoints, Warmup, BOND_CONTRACT_INFO, CONFIG, EPOCH_STATE,
-        STAKING_POINTS, WARMUP,
+        assert_not_paused, pause_status, update_staking_points, Operation, StakingPoints, Warmup,
+        BOND_CONTRACT_INFO, CONFIG, EPOCH_STATE, OHM_DECIMALS, STAKING_POINTS, WARMUP,
     },
     ContractError,
 };
@@ -22,6 +25,20 @@ pub const TOTAL_GONS: u128 = u128::MAX - (u128::MAX % INITIAL_FRAGMENTS_SUPPLY);
 pub const MAX_SUPPLY: u128 = u64::MAX as u128;
 
 pub fn rebase(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Rebase)?;
+    _rebase(deps, env)
+}
+
+/// Called by wasmx at the beginning of every block once the contract is registered.
+/// wasmx deregisters contracts whose begin blocker fails, so a paused rebase is skipped instead
+pub fn begin_blocker(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    if pause_status(deps.storage)?.is_paused(&Operation::Rebase) {
+        return Ok(Response::new());
+    }
+    _rebase(deps, env)
+}
+
+fn _rebase(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
     let mut config = CONFIG.load(deps.storage)?;
     let mut epoch_state = EPOCH_STATE.load(deps.storage)?;
 
@@ -63,7 +80,17 @@ pub fn rebase(deps: DepsMut, env: Env, _info: MessageInfo) -> Result<Response, C
     EPOCH_STATE.save(deps.storage, &epoch_state)?;
     CONFIG.save(deps.storage, &config)?;
 
-    Ok(Response::new().add_messages(msg))
+    let hooks = hook_msgs(
+        deps.storage,
+        StakeChangedHookMsg {
+            action: StakeAction::Rebase,
+            address: env.contract.address.to_string(),
+            delta: rebase_amount.try_into()?,
+            new_balance: (Uint256::from(current_balance) + rebase_amount).try_into()?,
+        },
+    )?;
+
+    Ok(Response::new().add_messages(msg).add_submessages(hooks))
 }
 
 pub fn execute_stake(
@@ -72,6 +99,7 @@ pub fn execute_stake(
     info: MessageInfo,
     to: String,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Stake)?;
     let deposited_amount = deposit_one_coin(info, base_denom(&env))?;
     let config = CONFIG.load(deps.storage)?;
 
@@ -81,7 +109,7 @@ pub fn execute_stake(
 
     let to_addr = deps.api.addr_validate(&to)?;
     // Add to current user Warmup
-    WARMUP.update(deps.storage, &to_addr, |w| match w {
+    let warmup = WARMUP.update(deps.storage, &to_addr, |w| match w {
         None => Ok::<_, ContractError>(Warmup {
             amount: deposited_amount,
             mint_amount: mint_amount.try_into()?,
@@ -104,7 +132,17 @@ pub fn execute_stake(
         }],
     });
 
-    Ok(Response::new().add_message(msg))
+    let hooks = hook_msgs(
+        deps.storage,
+        StakeChangedHookMsg {
+            action: StakeAction::Stake,
+            address: to_addr.to_string(),
+            delta: mint_amount.try_into()?,
+            new_balance: warmup.mint_amount,
+        },
+    )?;
+
+    Ok(Response::new().add_message(msg).add_submessages(hooks))
 }
 
 pub fn execute_claim(
@@ -113,14 +151,15 @@ pub fn execute_claim(
     info: MessageInfo,
     to: String,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Claim)?;
     let config = CONFIG.load(deps.storage)?;
     // We transfer coins from the warmup contract to us
     let claim = WARMUP.load(deps.storage, &info.sender)?;
     WARMUP.remove(deps.storage, &info.sender);
 
-    let msgs = if claim.end > env.block.time {
+    let (msgs, hook_msg) = if claim.end > env.block.time {
         // We send the ohm back to the sender
-        vec![CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
+        let msgs = vec![CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
             contract_addr: config.warmup_address.unwrap().to_string(),
             msg: to_json_binary(&cw1_whitelist::msg::ExecuteMsg::Execute {
                 msgs: vec![CosmosMsg::Bank::<Empty>(BankMsg::Send {
@@ -129,12 +168,26 @@ pub fn execute_claim(
                 })],
             })?,
             funds: vec![],
-        })]
+        })];
+        // The warmup is cancelled, no sOHM is minted
+        let hook_msg = StakeChangedHookMsg {
+            action: StakeAction::Claim,
+            address: info.sender.to_string(),
+            delta: Uint128::zero(),
+            new_balance: staking_token_balance(deps.as_ref(), &info.sender)?,
+        };
+        (msgs, hook_msg)
     } else {
         let to_addr = deps.api.addr_validate(&to)?;
         update_staking_points(deps.branch(), env.clone(), &to_addr, claim.mint_amount)?;
+        let hook_msg = StakeChangedHookMsg {
+            action: StakeAction::Claim,
+            address: to_addr.to_string(),
+            delta: claim.mint_amount,
+            new_balance: staking_token_balance(deps.as_ref(), &to_addr)? + claim.mint_amount,
+        };
         // We mint some sOHM to the to address
-        vec![
+        let msgs = vec![
             CosmosMsg::Wasm(cosmwasm_std::WasmMsg::Execute {
                 contract_addr: config.warmup_address.unwrap().to_string(),
                 msg: to_json_binary(&cw1_whitelist::msg::ExecuteMsg::Execute {
@@ -153,10 +206,13 @@ pub fn execute_claim(
                 })?,
                 funds: vec![],
             }),
-        ]
+        ];
+        (msgs, hook_msg)
     };
 
-    Ok(Response::new().add_messages(msgs))
+    let hooks = hook_msgs(deps.storage, hook_msg)?;
+
+    Ok(Response::new().add_messages(msgs).add_submessages(hooks))
 }
 
 pub fn unstake(
@@ -166,6 +222,7 @@ pub fn unstake(
     to: String,
     amount: Uint128,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, Operation::Unstake)?;
     let exchange_rate = current_exchange_rate(deps.as_ref(), &env, None)?;
 
     let redeem_amount = Uint256::from(amount) * exchange_rate;
@@ -202,7 +259,20 @@ pub fn unstake(
         }],
     });
 
-    Ok(Response::new().add_message(burn_msg).add_message(send_msg))
+    let hooks = hook_msgs(
+        deps.storage,
+        StakeChangedHookMsg {
+            action: StakeAction::Unstake,
+            address: info.sender.to_string(),
+            delta: amount,
+            new_balance: staking_token_balance(deps.as_ref(), &info.sender)?.saturating_sub(amount),
+        },
+    )?;
+
+    Ok(Response::new()
+        .add_message(burn_msg)
+        .add_message(send_msg)
+        .add_submessages(hooks))
 }
 
 pub fn mint(
@@ -226,6 +296,17 @@ pub fn mint(
     Ok(Response::new().add_messages(mint_msgs(&env, base_denom(&env), to, amount)))
 }
 
+/// Burns the OHM sent by the caller, inverse bonds use it to retire the OHM they buy back
+pub fn burn(env: Env, info: MessageInfo) -> Result<Response, ContractError> {
+    let denom = base_denom(&env);
+    let amount = deposit_one_coin(info, denom.clone())?;
+
+    Ok(Response::new()
+        .add_message(burn_msg(&env, denom, amount))
+        .add_attribute("action", "burn")
+        .add_attribute("amount", amount))
+}
+
 pub fn instantiate_staking_token(
     deps: DepsMut,
     env: Env,
@@ -235,11 +316,9 @@ pub fn instantiate_staking_token(
     staking_name: String,
     cw1_code_id: u64,
 ) -> Result<Response, ContractError> {
+    cw_ownable::assert_owner(deps.storage, &info.sender)?;
     let config = CONFIG.load(deps.storage)?;
 
-    if info.sender != config.admin {
-        return Err(ContractError::Unauthorized {});
-    }
     if config.staking_denom_address.is_some() {
         return Err(ContractError::Unauthorized {});
     }
@@ -251,7 +330,7 @@ pub fn instantiate_staking_token(
             msg: to_json_binary(&cw20_base::msg::InstantiateMsg {
                 name: staking_name,
                 symbol: staking_symbol,
-                decimals: 6,
+                decimals: OHM_DECIMALS,
                 initial_balances: vec![],
                 mint: Some(MinterResponse {
                     minter: env.contract.address.to_string(),
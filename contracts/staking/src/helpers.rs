@@ -1,8 +1,15 @@
 use cosmos_sdk_proto::traits::Message;
-use cosmwasm_std::{Coin, CosmosMsg, Env, MessageInfo, Uint128};
-use injective_std::types::injective::tokenfactory::v1beta1::{MsgCreateDenom, MsgMint};
+use cosmwasm_std::{
+    Addr, BankMsg, Coin, CosmosMsg, Deps, Env, MessageInfo, StdResult, Storage, SubMsg, Uint128,
+};
+use injective_std::types::injective::tokenfactory::v1beta1::{MsgBurn, MsgCreateDenom, MsgMint};
 
-use crate::ContractError;
+use crate::{
+    contract::{HOOK_GAS_LIMIT, HOOK_REPLY},
+    msg::StakeChangedHookMsg,
+    state::HOOKS,
+    ContractError,
+};
 
 pub fn deposit_one_coin(info: MessageInfo, denom: String) -> Result<Uint128, ContractError> {
     // Verify the funds
@@ -57,3 +64,42 @@ pub fn mint_msgs(
         }),
     ]
 }
+
+/// Burns `amount` of `denom` from the balance of the contract, it has to be the denom admin
+pub fn burn_msg(env: &Env, denom: String, amount: Uint128) -> CosmosMsg {
+    CosmosMsg::Stargate {
+        type_url: MsgBurn::TYPE_URL.to_string(),
+        value: MsgBurn {
+            sender: env.contract.address.to_string(),
+            amount: Some(injective_std::types::cosmos::base::v1beta1::Coin {
+                denom,
+                amount: amount.to_string(),
+            }),
+        }
+        .encode_to_vec()
+        .into(),
+    }
+}
+
+/// Notifies every registered hook
+/// Failures are caught in the reply and the gas is capped so that a broken consumer can't block staking operations
+pub fn hook_msgs(storage: &dyn Storage, msg: StakeChangedHookMsg) -> StdResult<Vec<SubMsg>> {
+    HOOKS.prepare_hooks(storage, |hook| {
+        Ok(
+            SubMsg::reply_on_error(msg.clone().into_cosmos_msg(hook)?, HOOK_REPLY)
+                .with_gas_limit(HOOK_GAS_LIMIT),
+        )
+    })
+}
+
+/// Sends every native balance of the contract to `recipient`
+pub fn sweep_msgs(deps: Deps, env: &Env, recipient: &Addr) -> StdResult<Vec<CosmosMsg>> {
+    let balances = deps.querier.query_all_balances(&env.contract.address)?;
+    if balances.is_empty() {
+        return Ok(vec![]);
+    }
+    Ok(vec![CosmosMsg::Bank(BankMsg::Send {
+        to_address: recipient.to_string(),
+        amount: balances,
+    })])
+}
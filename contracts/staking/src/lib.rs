@@ -8,4 +8,5 @@ pub use crate::error::ContractError;
 pub mod helpers;
 #[cfg(feature = "interface")]
 pub mod interface;
+pub mod migrate;
 pub mod response;
@@ -0,0 +1,110 @@
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Decimal256, DepsMut};
+use cw_storage_plus::Item;
+use semver::Version;
+
+use crate::{
+    state::{Config, CONFIG},
+    ContractError,
+};
+
+/// Config layout used before cw2 versioning, the admin was stored in the config
+#[cw_serde]
+struct LegacyConfig {
+    pub epoch_length: u64,
+    pub epoch_apr: Decimal256,
+    pub next_epoch_apr: Option<Decimal256>,
+    pub admin: Addr,
+    pub staking_denom_address: Option<Addr>,
+    pub warmup_address: Option<Addr>,
+    pub warmup_length: u64,
+}
+
+const LEGACY_CONFIG: Item<LegacyConfig> = Item::new("config");
+
+/// Transforms the storage written by `from` into the current layout.
+/// `from` is `None` for contracts instantiated before cw2 versioning.
+/// Only the config changed, `WARMUP` and the other maps kept their layout
+pub fn migrate_storage(deps: DepsMut, from: Option<&Version>) -> Result<(), ContractError> {
+    if from.is_none() {
+        migrate_legacy_config(deps)?;
+    }
+    Ok(())
+}
+
+/// Moves the admin from the config to cw-ownable
+fn migrate_legacy_config(deps: DepsMut) -> Result<(), ContractError> {
+    let legacy = LEGACY_CONFIG.load(deps.storage)?;
+
+    cw_ownable::initialize_owner(deps.storage, deps.api, Some(legacy.admin.as_str()))?;
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            epoch_length: legacy.epoch_length,
+            epoch_apr: legacy.epoch_apr,
+            next_epoch_apr: legacy.next_epoch_apr,
+            staking_denom_address: legacy.staking_denom_address,
+            warmup_address: legacy.warmup_address,
+            warmup_length: legacy.warmup_length,
+            guardian: None,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::testing::{mock_dependencies, mock_env};
+    use cosmwasm_std::{Addr, Decimal256, Storage, Timestamp, Uint128};
+
+    use crate::contract::{migrate, CONTRACT_NAME, CONTRACT_VERSION};
+    use crate::msg::MigrateMsg;
+    use crate::state::{Warmup, CONFIG, WARMUP};
+
+    #[test]
+    pub fn legacy_admin_becomes_the_owner() -> anyhow::Result<()> {
+        let mut deps = mock_dependencies();
+        deps.storage.set(
+            b"config",
+            concat!(
+                r#"{"epoch_length":100,"epoch_apr":"0.1","next_epoch_apr":null,"admin":"admin","#,
+                r#""staking_denom_address":"token","warmup_address":"warmup","warmup_length":50}"#
+            )
+            .as_bytes(),
+        );
+        deps.storage.set(
+            b"\x00\x0bwarmup_infoalice",
+            br#"{"amount":"100","end":"1000000000","mint_amount":"90"}"#,
+        );
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg {})?;
+
+        let version = cw2::get_contract_version(&deps.storage)?;
+        assert_eq!(version.contract, CONTRACT_NAME);
+        assert_eq!(version.version, CONTRACT_VERSION);
+        assert_eq!(
+            cw_ownable::get_ownership(&deps.storage)?.owner,
+            Some(Addr::unchecked("admin"))
+        );
+        let config = CONFIG.load(&deps.storage)?;
+        assert_eq!(config.epoch_length, 100);
+        assert_eq!(config.epoch_apr, Decimal256::percent(10));
+        assert_eq!(config.staking_denom_address, Some(Addr::unchecked("token")));
+        assert_eq!(config.warmup_address, Some(Addr::unchecked("warmup")));
+        assert_eq!(config.warmup_length, 50);
+        assert_eq!(config.guardian, None);
+
+        // Warmups are read as is
+        assert_eq!(
+            WARMUP.load(&deps.storage, &Addr::unchecked("alice"))?,
+            Warmup {
+                amount: Uint128::new(100),
+                end: Timestamp::from_seconds(1),
+                mint_amount: Uint128::new(90),
+            }
+        );
+
+        Ok(())
+    }
+}
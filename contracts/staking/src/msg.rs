@@ -1,20 +1,24 @@
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::{Decimal256, Uint128};
+use cosmwasm_std::{to_json_binary, Binary, CosmosMsg, Decimal256, StdResult, Uint128, WasmMsg};
+use cw_controllers::HooksResponse;
+use cw_ownable::{cw_ownable_execute, cw_ownable_query};
 
-use crate::state::{EpochState, StakingPoints};
+use crate::state::{EpochState, Operation, PauseStatus, StakingPoints};
 
 /// Message type for `instantiate` entry_point
 #[cw_serde]
 pub struct InstantiateMsg {
-    pub admin: Option<String>,
+    pub owner: Option<String>,
     pub epoch_length: u64,
     pub epoch_apr: Decimal256,
     pub first_epoch_time: u64,
     pub initial_balances: Vec<(String, Uint128)>,
     pub warmup_length: u64,
+    pub guardian: Option<String>,
 }
 
 /// Message type for `execute` entry_point
+#[cw_ownable_execute]
 #[cw_serde]
 #[cfg_attr(feature = "interface", derive(cw_orch::ExecuteFns))]
 pub enum ExecuteMsg {
@@ -41,20 +45,61 @@ pub enum ExecuteMsg {
         to: String,
         amount: Uint128,
     },
+    /// Burns the OHM sent with the message
+    #[cfg_attr(feature = "interface", payable)]
+    Burn {},
     UpdateConfig {
-        admin: Option<String>,
         epoch_length: Option<u64>,
         epoch_apr: Option<Decimal256>,
         add_bond: Option<Vec<BondContractsElem>>,
         remove_bond: Option<Vec<String>>,
+        guardian: Option<String>,
+    },
+    AddHook {
+        addr: String,
+    },
+    RemoveHook {
+        addr: String,
+    },
+    /// Removes the guardian, only the owner can pause afterwards
+    RemoveGuardian {},
+    /// Can be called by the guardian or the owner
+    Pause {
+        operations: Vec<Operation>,
+    },
+    /// Can only be called by the owner
+    Unpause {
+        operations: Vec<Operation>,
+    },
+}
+
+/// Message type for `sudo` entry_point, only the chain governance can call it
+#[cw_serde]
+pub enum SudoMsg {
+    /// Replaces the owner without going through the two-step transfer
+    UpdateOwner {
+        owner: String,
+    },
+    Pause {
+        operations: Vec<Operation>,
+    },
+    Unpause {
+        operations: Vec<Operation>,
+    },
+    /// Sends all the funds held by the contract to the recovery address
+    SweepFunds {
+        recipient: String,
     },
+    /// Sent by wasmx every block, rebases once the current epoch has ended
+    BeginBlocker {},
 }
 
 /// Message type for `migrate` entry_point
 #[cw_serde]
-pub enum MigrateMsg {}
+pub struct MigrateMsg {}
 
 /// Message type for `query` entry_point
+#[cw_ownable_query]
 #[cw_serde]
 #[derive(QueryResponses)]
 #[cfg_attr(feature = "interface", derive(cw_orch::QueryFns))]
@@ -71,6 +116,10 @@ pub enum QueryMsg {
     StakingPoints { address: String },
     #[returns(StakingPoints)]
     RawStakingPoints { address: String },
+    #[returns(HooksResponse)]
+    Hooks {},
+    #[returns(PauseStatus)]
+    PauseStatus {},
 }
 
 #[cw_serde]
@@ -78,11 +127,11 @@ pub struct ConfigResponse {
     pub epoch_length: u64,
     pub epoch_apr: Decimal256,
     pub next_epoch_apr: Option<Decimal256>,
-    pub admin: String,
     pub ohm_denom: String,
     pub sohm_address: String,
     pub warmup_length: u64,
     pub warmup_address: String,
+    pub guardian: Option<String>,
 }
 
 #[cw_serde]
@@ -95,3 +144,50 @@ pub struct BondContractsElem {
     pub bond_token: String,
     pub bond_address: String,
 }
+
+/// Action that triggered a [`StakeChangedHookMsg`]
+#[cw_serde]
+pub enum StakeAction {
+    /// OHM was deposited into the warmup. `delta` and `new_balance` are the sOHM amounts pending in warmup
+    Stake,
+    /// The warmup was claimed. `delta` is the sOHM minted (zero if the warmup was cancelled) and `new_balance` the sOHM balance
+    Claim,
+    /// sOHM was burned. `delta` is the burned sOHM amount and `new_balance` the remaining sOHM balance
+    Unstake,
+    /// A new epoch started. `address` is the staking contract, `delta` the minted OHM and `new_balance` the OHM held by the contract
+    Rebase,
+}
+
+/// Message sent to every registered hook when a staked balance changes
+#[cw_serde]
+pub struct StakeChangedHookMsg {
+    pub action: StakeAction,
+    pub address: String,
+    pub delta: Uint128,
+    pub new_balance: Uint128,
+}
+
+impl StakeChangedHookMsg {
+    /// serializes the message
+    pub fn into_json_binary(self) -> StdResult<Binary> {
+        let msg = StakeChangedExecuteMsg::StakeChangedHook(self);
+        to_json_binary(&msg)
+    }
+
+    /// creates a cosmos_msg sending this struct to the named contract
+    pub fn into_cosmos_msg<T: Into<String>>(self, contract_addr: T) -> StdResult<CosmosMsg> {
+        let msg = self.into_json_binary()?;
+        let execute = WasmMsg::Execute {
+            contract_addr: contract_addr.into(),
+            msg,
+            funds: vec![],
+        };
+        Ok(execute.into())
+    }
+}
+
+/// Execute message hook receivers need to implement
+#[cw_serde]
+pub enum StakeChangedExecuteMsg {
+    StakeChangedHook(StakeChangedHookMsg),
+}
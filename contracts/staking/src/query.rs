@@ -82,11 +82,11 @@ pub fn query_config(deps: Deps, env: Env) -> Result<ConfigResponse, ContractErro
         epoch_length: config.epoch_length,
         epoch_apr: config.epoch_apr,
         next_epoch_apr: config.next_epoch_apr,
-        admin: config.admin.to_string(),
         ohm_denom: base_denom(&env),
         sohm_address: staking_token_addr(deps)?.to_string(),
         warmup_length: config.warmup_length,
         warmup_address: config.warmup_address.unwrap().to_string(),
+        guardian: config.guardian.map(|g| g.to_string()),
     })
 }
 
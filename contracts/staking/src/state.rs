@@ -1,5 +1,6 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Decimal256, Deps, DepsMut, Env, Order, Timestamp, Uint128};
+use cosmwasm_std::{Addr, Decimal256, Deps, DepsMut, Env, Order, Storage, Timestamp, Uint128};
+use cw_controllers::Hooks;
 use cw_storage_plus::{Item, Map};
 
 use crate::{
@@ -13,18 +14,22 @@ pub const EPOCH_STATE: Item<EpochState> = Item::new("epoch_state");
 pub const BOND_CONTRACT_INFO: Map<&Addr, BondContractInfo> = Map::new("minter_info");
 pub const WARMUP: Map<&Addr, Warmup> = Map::new("warmup_info");
 pub const STAKING_POINTS: Map<&Addr, StakingPoints> = Map::new("staking_points");
+pub const HOOKS: Hooks = Hooks::new("hooks");
+pub const PAUSED: Item<PauseStatus> = Item::new("paused");
 
 pub const BASE_TOKEN_DENOM: &str = "base_token";
+/// Decimals of OHM, the staking token uses the same
+pub const OHM_DECIMALS: u8 = 6;
 
 #[cw_serde]
 pub struct Config {
     pub epoch_length: u64,
     pub epoch_apr: Decimal256,
     pub next_epoch_apr: Option<Decimal256>,
-    pub admin: Addr,
     pub staking_denom_address: Option<Addr>,
     pub warmup_address: Option<Addr>,
     pub warmup_length: u64,
+    pub guardian: Option<Addr>,
 }
 #[cw_serde]
 pub struct EpochState {
@@ -52,6 +57,66 @@ pub struct Warmup {
     pub mint_amount: Uint128,
 }
 
+/// Operations the guardian can pause
+#[cw_serde]
+pub enum Operation {
+    Stake,
+    Claim,
+    Unstake,
+    Rebase,
+}
+
+#[cw_serde]
+#[derive(Default)]
+pub struct PauseStatus {
+    pub stake: bool,
+    pub claim: bool,
+    pub unstake: bool,
+    pub rebase: bool,
+}
+
+impl PauseStatus {
+    pub fn is_paused(&self, operation: &Operation) -> bool {
+        match operation {
+            Operation::Stake => self.stake,
+            Operation::Claim => self.claim,
+            Operation::Unstake => self.unstake,
+            Operation::Rebase => self.rebase,
+        }
+    }
+
+    pub fn set(&mut self, operation: &Operation, paused: bool) {
+        match operation {
+            Operation::Stake => self.stake = paused,
+            Operation::Claim => self.claim = paused,
+            Operation::Unstake => self.unstake = paused,
+            Operation::Rebase => self.rebase = paused,
+        }
+    }
+}
+
+pub fn pause_status(storage: &dyn Storage) -> Result<PauseStatus, ContractError> {
+    Ok(PAUSED.may_load(storage)?.unwrap_or_default())
+}
+
+pub fn set_paused(
+    storage: &mut dyn Storage,
+    operations: &[Operation],
+    paused: bool,
+) -> Result<(), ContractError> {
+    let mut status = pause_status(storage)?;
+    operations.iter().for_each(|op| status.set(op, paused));
+    PAUSED.save(storage, &status)?;
+    Ok(())
+}
+
+pub fn assert_not_paused(storage: &dyn Storage, operation: Operation) -> Result<(), ContractError> {
+    if pause_status(storage)?.is_paused(&operation) {
+        return Err(ContractError::Paused { operation });
+    }
+    Ok(())
+}
+
 pub fn bond_contracts(deps: Deps) -> Result<BondContractsResponse, ContractError> {
     let active_bonds: Result<Vec<_>, _> = BOND_CONTRACT_INFO
         .range(deps.storage, None, None, Order::Descending)
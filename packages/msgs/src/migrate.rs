@@ -0,0 +1,108 @@
+use cosmwasm_std::{attr, Attribute, StdError, Storage};
+use semver::Version;
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq)]
+pub enum MigrateError {
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    #[error("Can't migrate from contract {stored}, expected {expected}")]
+    WrongContract { stored: String, expected: String },
+
+    #[error("Can't downgrade from version {stored} to {new}")]
+    CannotDowngrade { stored: String, new: String },
+
+    #[error("Semver parsing error: {0}")]
+    SemVer(String),
+}
+
+impl From<semver::Error> for MigrateError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
+}
+
+/// For the contracts returning `StdError` or the errors of cw-plus contracts
+impl From<MigrateError> for StdError {
+    fn from(err: MigrateError) -> Self {
+        match err {
+            MigrateError::Std(err) => err,
+            err => StdError::generic_err(err.to_string()),
+        }
+    }
+}
+
+/// Checks that the contract stored in cw2 is `name` at a version up to `version`.
+/// Returns the stored version, `None` for contracts instantiated before cw2 versioning
+pub fn assert_can_migrate(
+    storage: &dyn Storage,
+    name: &str,
+    version: &str,
+) -> Result<Option<Version>, MigrateError> {
+    let Some(stored) = cw2::CONTRACT.may_load(storage)? else {
+        return Ok(None);
+    };
+    if stored.contract != name {
+        return Err(MigrateError::WrongContract {
+            stored: stored.contract,
+            expected: name.to_string(),
+        });
+    }
+    let stored_version: Version = stored.version.parse()?;
+    if stored_version > version.parse::<Version>()? {
+        return Err(MigrateError::CannotDowngrade {
+            stored: stored.version,
+            new: version.to_string(),
+        });
+    }
+    Ok(Some(stored_version))
+}
+
+/// Attributes of the response to a migration from `from` to `to`
+pub fn migrate_attributes(from: Option<&Version>, to: &str) -> Vec<Attribute> {
+    vec![
+        attr("action", "migrate"),
+        attr(
+            "from_version",
+            from.map(|v| v.to_string()).unwrap_or_default(),
+        ),
+        attr("to_version", to),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::MockStorage;
+
+    use super::*;
+
+    #[test]
+    fn versions_are_checked() {
+        let mut storage = MockStorage::new();
+        assert_eq!(
+            assert_can_migrate(&storage, "crates.io:bond", "0.1.0"),
+            Ok(None)
+        );
+
+        cw2::set_contract_version(&mut storage, "crates.io:bond", "0.1.0").unwrap();
+        assert_eq!(
+            assert_can_migrate(&storage, "crates.io:bond", "0.1.0"),
+            Ok(Some(Version::new(0, 1, 0)))
+        );
+        assert_eq!(
+            assert_can_migrate(&storage, "crates.io:bond", "0.0.1"),
+            Err(MigrateError::CannotDowngrade {
+                stored: "0.1.0".to_string(),
+                new: "0.0.1".to_string(),
+            })
+        );
+        assert_eq!(
+            assert_can_migrate(&storage, "crates.io:lock", "0.1.0"),
+            Err(MigrateError::WrongContract {
+                stored: "crates.io:bond".to_string(),
+                expected: "crates.io:lock".to_string(),
+            })
+        );
+    }
+}
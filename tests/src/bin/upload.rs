@@ -36,6 +36,9 @@ pub fn main() -> anyhow::Result<()> {
         staking_name: "sSHOGUN".to_string(),
         warmup_length: 50,
         cw1_code_id: 5,
+        treasury: chain.sender().to_string(),
+        note_name: "Shogun bond notes".to_string(),
+        note_symbol: "SBOND".to_string(),
     })?;
 
     Ok(())
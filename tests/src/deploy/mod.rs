@@ -1,2 +1,3 @@
 pub mod instantiate;
 pub mod upload;
+pub mod wasmx;
@@ -1,27 +1,41 @@
 use std::{collections::HashMap, path::PathBuf};
 
 use bond::interface::Bond;
+use bond::msg::ExecuteMsgFns as _;
+use bond::msg::QueryMsgFns as _;
 use bond::state::Terms;
+use bond_note::interface::BondNote;
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{coins, Addr, Decimal256};
+use cosmwasm_std::{coins, Addr, Decimal256, Uint128};
+use cw_asset::AssetInfoUnchecked;
 use cw_orch::{
-    contract::interface_traits::{ContractInstance, CwOrchInstantiate, CwOrchUpload},
+    contract::interface_traits::{
+        CallAs, ContractInstance, CwOrchExecute, CwOrchInstantiate, CwOrchMigrate, CwOrchUpload,
+    },
     deploy::Deploy,
-    environment::CwEnv,
-    prelude::CwOrchError,
+    environment::{CwEnv, TxHandler},
+    prelude::{CwOrchError, IndexResponse},
 };
+use cw_ownable::Action;
+use cw_utils::Expiration;
 use staking_contract::interface::Staking;
 use staking_contract::msg::BondContractsElem;
 use staking_contract::msg::ExecuteMsgFns as _;
 use staking_contract::msg::QueryMsgFns as _;
 use staking_token::interface::StakingToken;
 
-pub const BOND_CODE_ID: &str = "bond-code-id";
+/// Label of the bond depository in the staking contract
+pub const DEPOSITORY: &str = "depository";
 
 #[derive(Clone)]
 pub struct Shogun<Chain: CwEnv> {
     pub staking: Staking<Chain>,
-    pub bonds: HashMap<String, Bond<Chain>>,
+    /// Bond depository, it holds all the markets
+    pub bond: Bond<Chain>,
+    /// Market ids by quote token
+    pub markets: HashMap<String, u64>,
+    /// NFT contract of the tokenized bond notes, instantiated by the depository
+    pub bond_note: BondNote<Chain>,
     pub staking_token: StakingToken<Chain>,
 }
 impl<Chain: CwEnv> Deploy<Chain> for Shogun<Chain> {
@@ -33,7 +47,8 @@ impl<Chain: CwEnv> Deploy<Chain> for Shogun<Chain> {
         let shogun = Self::new(chain);
 
         shogun.staking.upload()?;
-        shogun.bonds.get(BOND_CODE_ID).unwrap().upload()?;
+        shogun.bond.upload()?;
+        shogun.bond_note.upload()?;
         shogun.staking_token.upload()?;
 
         Ok(shogun)
@@ -56,35 +71,24 @@ impl<Chain: CwEnv> Deploy<Chain> for Shogun<Chain> {
     ) -> Vec<Box<&mut dyn cw_orch::prelude::ContractInstance<Chain>>> {
         let staking_box: Box<&mut dyn cw_orch::prelude::ContractInstance<Chain>> =
             Box::new(&mut self.staking);
+        let bond_box: Box<&mut dyn cw_orch::prelude::ContractInstance<Chain>> =
+            Box::new(&mut self.bond);
+        let note_box: Box<&mut dyn cw_orch::prelude::ContractInstance<Chain>> =
+            Box::new(&mut self.bond_note);
         let token_box: Box<&mut dyn cw_orch::prelude::ContractInstance<Chain>> =
             Box::new(&mut self.staking_token);
-        self.bonds
-            .iter_mut()
-            .map(|(_, d)| {
-                let boxed: Box<&mut dyn cw_orch::prelude::ContractInstance<Chain>> = Box::new(d);
-                boxed
-            })
-            .chain([staking_box, token_box])
-            .collect()
+        vec![staking_box, bond_box, note_box, token_box]
     }
 
     fn load_from(chain: Chain) -> Result<Self, Self::Error> {
-        let mut shogun = Self::new(chain.clone());
+        let mut shogun = Self::new(chain);
 
-        // We get all the bonds contracts and their names
-        let bonds = shogun.staking.bonds()?;
-
-        // We create bond contracts
-        shogun.bonds = bonds
-            .bonds
+        // We get all the markets of the depository
+        shogun.markets = shogun
+            .bond
+            .markets()?
             .into_iter()
-            .map(|bond_config| {
-                let bond_contract = Bond::new(
-                    Self::bond_contract_name(bond_config.bond_token.clone()),
-                    chain.clone(),
-                );
-                (bond_config.bond_token, bond_contract)
-            })
+            .map(|market| (market.market.quote_id(), market.id))
             .collect();
 
         Ok(shogun)
@@ -103,11 +107,14 @@ impl<Chain: CwEnv> Shogun<Chain> {
     pub fn new(chain: Chain) -> Self {
         let staking = Staking::new("shogun:staking", chain.clone());
         let bond = Bond::new("shogun:bond", chain.clone());
+        let bond_note = BondNote::new("shogun:bond-note", chain.clone());
         let staking_token = StakingToken::new("shogun:staking-token", chain.clone());
 
         Self {
             staking,
-            bonds: HashMap::from_iter([(BOND_CODE_ID.to_string(), bond)]),
+            bond,
+            markets: HashMap::new(),
+            bond_note,
             staking_token,
         }
     }
@@ -117,11 +124,12 @@ impl<Chain: CwEnv> Shogun<Chain> {
         deploy_data: ShogunDeployment,
     ) -> Result<(), <Self as Deploy<Chain>>::Error> {
         let chain = self.staking.get_chain();
-        let sender = chain.sender().to_string();
+        let admin = chain.sender();
+        let sender = admin.to_string();
 
         self.staking.instantiate(
             &staking_contract::msg::InstantiateMsg {
-                admin: Some(sender),
+                owner: Some(sender),
                 epoch_length: deploy_data.epoch_length,
                 first_epoch_time: deploy_data.first_epoch_time,
                 epoch_apr: deploy_data.epoch_apr,
@@ -131,8 +139,9 @@ impl<Chain: CwEnv> Shogun<Chain> {
                     .map(|(recipient, amount)| (recipient, amount.into()))
                     .collect(),
                 warmup_length: deploy_data.warmup_length,
+                guardian: None,
             },
-            None,
+            Some(&admin),
             Some(&coins(
                 deploy_data.amount_to_create_denom * 2,
                 deploy_data.fee_token,
@@ -149,40 +158,22 @@ impl<Chain: CwEnv> Shogun<Chain> {
         let config = self.staking.config()?;
         self.staking_token
             .set_address(&Addr::unchecked(config.sohm_address));
-        Ok(())
-    }
-
-    pub fn bond_code_id(&self) -> Result<u64, <Self as Deploy<Chain>>::Error> {
-        self.bonds.get(BOND_CODE_ID).unwrap().code_id()
-    }
 
-    pub fn add_bond(
-        &mut self,
-        chain: Chain,
-        config: BondConfig,
-    ) -> Result<(), <Self as Deploy<Chain>>::Error> {
-        // Instantiate the bond on a new contract
-        let bond = Bond::new(
-            Self::bond_contract_name(config.bond_token_denom.clone()),
-            chain.clone(),
-        );
-        bond.set_code_id(self.bond_code_id()?);
-        bond.instantiate(
+        self.bond.instantiate(
             &bond::msg::InstantiateMsg {
-                admin: Some(chain.sender().to_string()),
-                principle: config.bond_token_denom.clone(),
+                owner: Some(admin.to_string()),
                 staking: self.staking.address()?.to_string(),
-                treasury: config.treasury,
-                terms: config.terms,
+                treasury: deploy_data.treasury,
+                guardian: None,
             },
-            None,
+            Some(&admin),
             None,
         )?;
 
         self.staking.update_config(
             Some(vec![BondContractsElem {
-                bond_token: config.bond_token_denom.clone(),
-                bond_address: bond.address()?.to_string(),
+                bond_token: DEPOSITORY.to_string(),
+                bond_address: self.bond.address()?.to_string(),
             }]),
             None,
             None,
@@ -190,13 +181,110 @@ impl<Chain: CwEnv> Shogun<Chain> {
             None,
         )?;
 
-        self.bonds.insert(config.bond_token_denom, bond);
+        self.bond.instantiate_note_nft(
+            self.bond_note.code_id()?,
+            deploy_data.note_name,
+            deploy_data.note_symbol,
+        )?;
+        let note_nft = self.bond.config()?.note_nft.unwrap();
+        self.bond_note.set_address(&Addr::unchecked(note_nft));
+        Ok(())
+    }
+
+    pub fn bond_code_id(&self) -> Result<u64, <Self as Deploy<Chain>>::Error> {
+        self.bond.code_id()
+    }
+
+    /// Registers the decimals of the quote token and opens a new market for it in the depository.
+    /// Returns the id of the market
+    pub fn add_market(
+        &mut self,
+        config: BondConfig,
+    ) -> Result<u64, <Self as Deploy<Chain>>::Error> {
+        self.bond.set_decimals(
+            AssetInfoUnchecked::native(config.bond_token_denom.clone()),
+            Some(config.bond_token_decimals),
+        )?;
+        let response = self.bond.create_market(
+            None,
+            None,
+            config.capacity,
+            config.capacity_in_quote,
+            None,
+            AssetInfoUnchecked::native(config.bond_token_denom.clone()),
+            config.terms,
+            None,
+        )?;
+        let market_id: u64 = response.event_attr_value("wasm", "market_id")?.parse()?;
+
+        self.markets.insert(config.bond_token_denom, market_id);
+
+        Ok(market_id)
+    }
+
+    /// Uploads the current staking and bond code and migrates all deployed contracts to it.
+    /// The staking token and the note NFT are administrated by the staking and bond contracts
+    /// and are not migrated here
+    pub fn migrate_all(&self) -> Result<(), <Self as Deploy<Chain>>::Error> {
+        self.staking.upload()?;
+        self.staking.migrate(
+            &staking_contract::msg::MigrateMsg {},
+            self.staking.code_id()?,
+        )?;
+
+        self.bond.upload()?;
+        self.bond.migrate(
+            &bond::msg::MigrateMsg { decimals: vec![] },
+            self.bond_code_id()?,
+        )?;
 
         Ok(())
     }
 
-    pub fn bond_contract_name(denom: String) -> String {
-        format!("shogun:bond-{}", denom)
+    /// Proposes `new_owner` as the owner of the staking and bond contracts.
+    /// Nothing changes until the new owner calls [`Shogun::accept_ownership`]
+    pub fn transfer_ownership(
+        &self,
+        new_owner: String,
+        expiry: Option<Expiration>,
+    ) -> Result<(), <Self as Deploy<Chain>>::Error> {
+        self.update_ownership(None, Action::TransferOwnership { new_owner, expiry })
+    }
+
+    /// Accepts a pending ownership transfer, signed by the pending owner
+    pub fn accept_ownership(
+        &self,
+        new_owner: &<Chain as TxHandler>::Sender,
+    ) -> Result<(), <Self as Deploy<Chain>>::Error> {
+        self.update_ownership(Some(new_owner), Action::AcceptOwnership)
+    }
+
+    /// Leaves all contracts without an owner, this can't be reverted
+    pub fn renounce_ownership(&self) -> Result<(), <Self as Deploy<Chain>>::Error> {
+        self.update_ownership(None, Action::RenounceOwnership)
+    }
+
+    fn update_ownership(
+        &self,
+        sender: Option<&<Chain as TxHandler>::Sender>,
+        action: Action,
+    ) -> Result<(), <Self as Deploy<Chain>>::Error> {
+        let staking = match sender {
+            Some(sender) => self.staking.call_as(sender),
+            None => self.staking.clone(),
+        };
+        staking.execute(
+            &staking_contract::msg::ExecuteMsg::UpdateOwnership(action.clone()),
+            None,
+        )?;
+
+        let bond = match sender {
+            Some(sender) => self.bond.call_as(sender),
+            None => self.bond.clone(),
+        };
+        bond.execute(&bond::msg::ExecuteMsg::UpdateOwnership(action), None)?;
+
+        Ok(())
     }
 }
 
@@ -212,11 +300,17 @@ pub struct ShogunDeployment {
     pub staking_name: String,
     pub warmup_length: u64,
     pub cw1_code_id: u64,
+    /// Receives the quote tokens of all bond markets
+    pub treasury: String,
+    pub note_name: String,
+    pub note_symbol: String,
 }
 
 #[cw_serde]
 pub struct BondConfig {
     pub bond_token_denom: String,
-    pub treasury: String,
+    pub bond_token_decimals: u8,
+    pub capacity_in_quote: bool,
+    pub capacity: Uint128,
     pub terms: Terms,
 }
@@ -0,0 +1,74 @@
+use cosmos_sdk_proto::traits::Message;
+use cw_orch::{
+    contract::interface_traits::ContractInstance,
+    deploy::Deploy,
+    environment::{CwEnv, TxHandler},
+    prelude::Stargate,
+};
+use injective_std::types::cosmos::bank::v1beta1::MsgSend;
+use injective_std::types::cosmos::base::v1beta1::Coin;
+use injective_std::types::injective::wasmx::v1::{
+    ContractRegistrationRequest, FundingMode, MsgRegisterContract, MsgRegisterContractResponse,
+};
+use prost_types::Any;
+
+use super::upload::Shogun;
+
+/// Gas settings used by wasmx when calling the begin blocker of the staking contract
+pub struct BeginBlockerRegistration {
+    pub gas_limit: u64,
+    pub gas_price: u64,
+    /// Amount of INJ sent to the contract, it pays for its own begin blocker executions
+    pub gas_funds: u128,
+}
+
+impl<Chain: CwEnv + Stargate> Shogun<Chain> {
+    /// Registers the staking contract with wasmx so that it rebases in its begin blocker.
+    /// The contract is funded in the same transaction, the sender must be its wasm admin
+    pub fn register_begin_blocker(
+        &self,
+        registration: BeginBlockerRegistration,
+    ) -> Result<<Chain as TxHandler>::Response, <Self as Deploy<Chain>>::Error> {
+        let chain = self.staking.get_chain();
+        let sender = chain.sender().to_string();
+        let contract_address = self.staking.address()?.to_string();
+
+        chain
+            .commit_any::<MsgRegisterContractResponse>(
+                vec![
+                    Any {
+                        type_url: MsgSend::TYPE_URL.to_string(),
+                        value: MsgSend {
+                            from_address: sender.clone(),
+                            to_address: contract_address.clone(),
+                            amount: vec![Coin {
+                                denom: "inj".to_string(),
+                                amount: registration.gas_funds.to_string(),
+                            }],
+                        }
+                        .encode_to_vec(),
+                    },
+                    Any {
+                        type_url: MsgRegisterContract::TYPE_URL.to_string(),
+                        value: MsgRegisterContract {
+                            sender: sender.clone(),
+                            contract_registration_request: Some(ContractRegistrationRequest {
+                                contract_address,
+                                gas_limit: registration.gas_limit,
+                                gas_price: registration.gas_price,
+                                should_pin_contract: false,
+                                is_migration_allowed: true,
+                                code_id: self.staking.code_id()?,
+                                admin_address: sender,
+                                granter_address: String::new(),
+                                funding_mode: FundingMode::SelfFunded as i32,
+                            }),
+                        }
+                        .encode_to_vec(),
+                    },
+                ],
+                None,
+            )
+            .map_err(Into::into)
+    }
+}
@@ -2,8 +2,8 @@ use std::rc::Rc;
 use std::str::FromStr;
 
 use super::unstake;
-use crate::deploy::upload::BondConfig;
-use crate::integration::test_constants::bond_terms_1::VESTING_TERM;
+use crate::deploy::upload::{BondConfig, DEPOSITORY};
+use crate::integration::test_constants::bond_terms_1::{MARKET_ID, VESTING_TERM};
 use crate::integration::test_constants::*;
 use crate::tokenfactory::assert_cw20_balance;
 use crate::{
@@ -16,8 +16,9 @@ use bond::interface::Bond;
 use bond::msg::ExecuteMsgFns as _;
 use bond::msg::QueryMsgFns as _;
 use bond::state::Adjustment;
-use bond::state::Terms;
+use bond::state::{PricingModel, Terms, VestingMode};
 use cosmwasm_std::{coins, Decimal256, Timestamp, Uint128};
+use cw_asset::AssetInfo;
 use cw_orch::injective_test_tube::injective_test_tube::{Account, SigningAccount};
 use cw_orch::injective_test_tube::InjectiveTestTube;
 use cw_orch::{
@@ -33,7 +34,12 @@ use staking_contract::state::StakingPoints;
 pub const WARMUP_LENGTH: u64 = 10_000;
 
 pub fn init() -> anyhow::Result<Shogun<InjectiveTestTube>> {
-    let chain = test_tube();
+    Ok(init_with_treasury()?.0)
+}
+
+pub fn init_with_treasury() -> anyhow::Result<(Shogun<InjectiveTestTube>, Rc<SigningAccount>)> {
+    let mut chain = test_tube();
+    let treasury = chain.init_account(vec![])?;
 
     let cw1 = Cw1Whitelist::new("cw1-whitelist", chain.clone());
     cw1.upload()?;
@@ -51,10 +57,13 @@ pub fn init() -> anyhow::Result<Shogun<InjectiveTestTube>> {
             staking_name: "sSHOGUN".to_string(),
             warmup_length: WARMUP_LENGTH,
             cw1_code_id: cw1.code_id()?,
+            treasury: treasury.address().to_string(),
+            note_name: "Shogun bond notes".to_string(),
+            note_symbol: "SBOND".to_string(),
         },
     )?;
 
-    Ok(shogun)
+    Ok((shogun, treasury))
 }
 
 pub fn init_bond() -> anyhow::Result<(
@@ -62,32 +71,27 @@ pub fn init_bond() -> anyhow::Result<(
     Bond<InjectiveTestTube>,
     Rc<SigningAccount>,
 )> {
-    let mut shogun = init()?;
-    let mut chain = shogun.staking.get_chain().clone();
+    let (mut shogun, treasury) = init_with_treasury()?;
 
     let _ = pretty_env_logger::try_init();
 
-    let treasury = chain.init_account(vec![])?;
-
-    let bond_denom = bond_terms_1::BOND_TOKEN.to_string();
-    shogun.add_bond(
-        chain.clone(),
-        BondConfig {
-            bond_token_denom: bond_denom.clone(),
-            treasury: treasury.address().to_string(),
-            terms: Terms {
-                control_variable: Decimal256::from_str(bond_terms_1::CONTROL_VARIABLE)?,
-                max_debt: bond_terms_1::MAX_DEBT.into(),
-                max_payout: Decimal256::from_str(bond_terms_1::MAX_PAYOUT)?,
-                minimum_price: Decimal256::from_str(bond_terms_1::MINIMUM_PRICE)?,
-                vesting_term: bond_terms_1::VESTING_TERM,
-            },
+    shogun.add_market(BondConfig {
+        bond_token_denom: bond_terms_1::BOND_TOKEN.to_string(),
+        bond_token_decimals: bond_terms_1::BOND_TOKEN_DECIMALS,
+        capacity_in_quote: false,
+        capacity: Uint128::MAX,
+        terms: Terms {
+            control_variable: Decimal256::from_str(bond_terms_1::CONTROL_VARIABLE)?,
+            max_debt: bond_terms_1::MAX_DEBT.into(),
+            max_payout: Decimal256::from_str(bond_terms_1::MAX_PAYOUT)?,
+            minimum_price: Decimal256::from_str(bond_terms_1::MINIMUM_PRICE)?,
+            vesting: VestingMode::FixedTerm(bond_terms_1::VESTING_TERM),
+            pricing: PricingModel::DebtRatio,
+            lock_payout: false,
         },
-    )?;
-
-    let bond_contract = shogun.bonds.get(&bond_denom).unwrap();
+    })?;
 
-    Ok((shogun.clone(), bond_contract.clone(), treasury))
+    Ok((shogun.clone(), shogun.bond.clone(), treasury))
 }
 
 pub fn stake_and_claim<Chain: CwEnv>(
@@ -125,16 +129,21 @@ fn deploy_check() -> anyhow::Result<()> {
     assert_eq!(
         config,
         staking_contract::msg::ConfigResponse {
-            admin: chain.sender().to_string(),
             epoch_apr: Decimal256::from_str(EPOCH_APR)?,
             next_epoch_apr: None,
             epoch_length: EPOCH_LENGTH,
             ohm_denom: shogun.staking.config()?.ohm_denom,
             sohm_address: shogun.staking.config()?.sohm_address,
             warmup_address: shogun.staking.config()?.warmup_address,
-            warmup_length: WARMUP_LENGTH
+            warmup_length: WARMUP_LENGTH,
+            guardian: None,
+            oracle: None,
         }
     );
+    assert_eq!(
+        shogun.staking.ownership()?.owner,
+        Some(chain.sender().to_string())
+    );
 
     let epoch_state = shogun.staking.epoch_state()?;
 
@@ -169,14 +178,25 @@ fn bond_check() -> anyhow::Result<()> {
     assert_eq!(
         bond_config,
         bond::msg::ConfigResponse {
-            admin: chain.sender().to_string(),
-            principle: bond_denom.clone(),
             staking: shogun.staking.address()?.to_string(),
             treasury: treasury.address().to_string(),
+            guardian: None,
+            note_nft: Some(shogun.bond_note.address()?.to_string()),
+            dao_fee: Decimal256::zero(),
+            dao_address: None,
+            lock_contract: None,
         }
     );
+    assert_eq!(
+        bond_contract.ownership()?.owner,
+        Some(chain.sender().to_string())
+    );
+
+    let market = bond_contract.market(MARKET_ID)?;
+    assert_eq!(market.market.quote_token, AssetInfo::native(&bond_denom));
+    assert_eq!(shogun.markets.get(&bond_denom), Some(&MARKET_ID));
 
-    let bond_terms = bond_contract.terms()?;
+    let bond_terms = bond_contract.terms(MARKET_ID)?;
 
     assert_eq!(
         bond_terms,
@@ -185,7 +205,9 @@ fn bond_check() -> anyhow::Result<()> {
             max_debt: bond_terms_1::MAX_DEBT.into(),
             max_payout: Decimal256::from_str(bond_terms_1::MAX_PAYOUT)?,
             minimum_price: Decimal256::from_str(bond_terms_1::MINIMUM_PRICE)?,
-            vesting_term: bond_terms_1::VESTING_TERM,
+            vesting: VestingMode::FixedTerm(bond_terms_1::VESTING_TERM),
+            pricing: PricingModel::DebtRatio,
+            lock_payout: false,
         }
     );
 
@@ -194,7 +216,7 @@ fn bond_check() -> anyhow::Result<()> {
     assert_eq!(
         bonds.bonds,
         vec![BondContractsElem {
-            bond_token: bond_denom.clone(),
+            bond_token: DEPOSITORY.to_string(),
             bond_address: bond_contract.address()?.to_string(),
         }]
     );
@@ -205,31 +227,26 @@ fn bond_check() -> anyhow::Result<()> {
 #[test]
 fn modify_staking_config() -> anyhow::Result<()> {
     let shogun = init()?;
-    let mut chain = shogun.staking.get_chain().clone();
-    let new_admin = chain.init_account(vec![])?;
 
     let new_apr = Decimal256::from_str("1.3493859798")?;
     let new_epoch_length = 842387;
 
-    shogun.staking.update_config(
-        None,
-        Some(new_admin.address().to_string()),
-        Some(new_apr),
-        Some(new_epoch_length),
-        None,
-    )?;
+    shogun
+        .staking
+        .update_config(None, Some(new_apr), Some(new_epoch_length), None, None)?;
 
     assert_eq!(
         shogun.staking.config()?,
         staking_contract::msg::ConfigResponse {
-            admin: new_admin.address().to_string(),
             epoch_apr: Decimal256::from_str(EPOCH_APR)?,
             next_epoch_apr: Some(new_apr),
             epoch_length: new_epoch_length,
             ohm_denom: shogun.staking.config()?.ohm_denom,
             sohm_address: shogun.staking.config()?.sohm_address,
             warmup_address: shogun.staking.config()?.warmup_address,
-            warmup_length: WARMUP_LENGTH
+            warmup_length: WARMUP_LENGTH,
+            guardian: None,
+            oracle: None,
         }
     );
 
@@ -240,15 +257,14 @@ fn modify_staking_config() -> anyhow::Result<()> {
 fn modify_bond_config() -> anyhow::Result<()> {
     let (shogun, bond_contract, _treasury) = init_bond()?;
     let mut chain = shogun.staking.get_chain().clone();
-    let new_admin = chain.init_account(vec![])?;
     let new_treasury = chain.init_account(vec![])?;
     let new_staking = chain.init_account(vec![])?;
 
-    let new_principle = "new_principle".to_string();
-
     bond_contract.update_config(
-        Some(new_admin.address().to_string()),
-        Some(new_principle.clone()),
+        None,
+        None,
+        None,
+        None,
         Some(new_staking.address().to_string()),
         Some(new_treasury.address().to_string()),
     )?;
@@ -256,15 +272,77 @@ fn modify_bond_config() -> anyhow::Result<()> {
     assert_eq!(
         bond_contract.config()?,
         bond::msg::ConfigResponse {
-            admin: new_admin.address().to_string(),
-            principle: new_principle,
             staking: new_staking.address().to_string(),
             treasury: new_treasury.address().to_string(),
+            guardian: None,
+            note_nft: Some(shogun.bond_note.address()?.to_string()),
+            dao_fee: Decimal256::zero(),
+            dao_address: None,
+            lock_contract: None,
         }
     );
     Ok(())
 }
 
+#[test]
+fn ownership_handover() -> anyhow::Result<()> {
+    let (shogun, bond_contract, _treasury) = init_bond()?;
+    let mut chain = shogun.staking.get_chain().clone();
+    let new_owner = chain.init_account(vec![])?;
+
+    shogun.transfer_ownership(new_owner.address().to_string(), None)?;
+
+    // The transfer is only pending until accepted
+    assert_eq!(
+        shogun.staking.ownership()?.owner,
+        Some(chain.sender().to_string())
+    );
+    assert_eq!(
+        bond_contract.ownership()?.pending_owner,
+        Some(new_owner.address().to_string())
+    );
+
+    shogun.accept_ownership(&new_owner)?;
+
+    assert_eq!(
+        shogun.staking.ownership()?.owner,
+        Some(new_owner.address().to_string())
+    );
+    assert_eq!(
+        bond_contract.ownership()?.owner,
+        Some(new_owner.address().to_string())
+    );
+
+    // The previous owner can't administrate the contracts anymore
+    bond_contract
+        .update_config(None, None, None, None, None, None)
+        .unwrap_err();
+    bond_contract
+        .call_as(&new_owner)
+        .update_config(None, None, None, None, None, None)?;
+
+    Ok(())
+}
+
+#[test]
+fn migrate_all_keeps_state() -> anyhow::Result<()> {
+    let (shogun, bond_contract, _treasury) = init_bond()?;
+
+    let staking_config = shogun.staking.config()?;
+    let bond_config = bond_contract.config()?;
+    let markets = bond_contract.markets()?;
+    let bond_code_id = shogun.bond_code_id()?;
+
+    shogun.migrate_all()?;
+
+    assert_ne!(shogun.bond_code_id()?, bond_code_id);
+    assert_eq!(shogun.staking.config()?, staking_config);
+    assert_eq!(bond_contract.config()?, bond_config);
+    assert_eq!(bond_contract.markets()?, markets);
+
+    Ok(())
+}
+
 #[test]
 fn modify_bond_adjust() -> anyhow::Result<()> {
     let (shogun, bond_contract, _treasury) = init_bond()?;
@@ -278,12 +356,13 @@ fn modify_bond_adjust() -> anyhow::Result<()> {
     bond_contract.update_adjustment(
         Some(new_add),
         Some(new_buffer),
+        MARKET_ID,
         Some(new_rate),
         Some(new_target),
     )?;
 
     assert_eq!(
-        bond_contract.adjustment()?,
+        bond_contract.adjustment(MARKET_ID)?,
         Adjustment {
             add: new_add,
             buffer: new_buffer,
@@ -305,28 +384,86 @@ fn modify_bond_terms() -> anyhow::Result<()> {
     let new_minimum_price = Decimal256::from_str("1387364876")?;
     let new_vesting_term = 24982987u64;
 
-    bond_contract.update_terms(Terms {
-        control_variable: new_control_variable,
-        max_debt: new_max_debt.into(),
-        max_payout: new_max_payout,
-        minimum_price: new_minimum_price,
-        vesting_term: new_vesting_term,
-    })?;
+    bond_contract.update_terms(
+        MARKET_ID,
+        Terms {
+            control_variable: new_control_variable,
+            max_debt: new_max_debt.into(),
+            max_payout: new_max_payout,
+            minimum_price: new_minimum_price,
+            vesting: VestingMode::FixedTerm(new_vesting_term),
+            pricing: PricingModel::DebtRatio,
+            lock_payout: false,
+        },
+    )?;
 
     assert_eq!(
-        bond_contract.terms()?,
+        bond_contract.terms(MARKET_ID)?,
         Terms {
             control_variable: new_control_variable,
             max_debt: new_max_debt.into(),
             max_payout: new_max_payout,
             minimum_price: new_minimum_price,
-            vesting_term: new_vesting_term
+            vesting: VestingMode::FixedTerm(new_vesting_term),
+            pricing: PricingModel::DebtRatio,
+            lock_payout: false,
         }
     );
 
     Ok(())
 }
 
+#[test]
+fn create_and_close_markets() -> anyhow::Result<()> {
+    let (mut shogun, bond_contract, _treasury) = init_bond()?;
+    let mut chain = shogun.staking.get_chain().clone();
+
+    let usd_market = shogun.add_market(BondConfig {
+        bond_token_denom: USD.to_string(),
+        bond_token_decimals: 6,
+        capacity_in_quote: true,
+        capacity: 1_000_000u128.into(),
+        terms: bond_contract.terms(MARKET_ID)?,
+    })?;
+    assert_eq!(usd_market, MARKET_ID + 1);
+
+    let markets = bond_contract.markets()?;
+    assert_eq!(
+        markets.iter().map(|m| m.id).collect::<Vec<_>>(),
+        vec![MARKET_ID, usd_market]
+    );
+    assert_eq!(markets[1].market.quote_token, AssetInfo::native(USD));
+    assert!(markets[1].market.capacity_in_quote);
+    assert!(markets[1].market.is_open());
+
+    bond_contract.close_market(MARKET_ID)?;
+    assert!(!bond_contract.market(MARKET_ID)?.market.is_open());
+
+    // Closed markets refuse deposits, the others stay open
+    let err = bond_contract
+        .deposit(
+            None,
+            chain.sender().to_string(),
+            MARKET_ID,
+            Decimal256::from_str("2.2")?,
+            None,
+            None,
+            &coins(bond_terms_1::DEPOSIT, bond_terms_1::BOND_TOKEN),
+        )
+        .unwrap_err();
+    assert!(err.to_string().contains("is closed"));
+    assert!(bond_contract.market(usd_market)?.market.is_open());
+
+    // Only the owner manages markets
+    let user = chain.init_account(vec![])?;
+    bond_contract
+        .call_as(&user)
+        .close_market(usd_market)
+        .unwrap_err();
+
+    Ok(())
+}
+
 #[test]
 fn full_operations() -> anyhow::Result<()> {
     let (shogun, bond_contract, _treasury) = init_bond()?;
@@ -339,19 +476,23 @@ fn full_operations() -> anyhow::Result<()> {
     stake_and_claim(&shogun, 10_000, Some(recipient.address().to_string()))?;
 
     // use a bond. Assert that the price goes up when more bonds are used
-    let bond_price_before = bond_contract.bond_price()?;
+    let bond_price_before = bond_contract.bond_price(MARKET_ID)?;
     bond_contract.deposit(
+        None,
         recipient.address().to_string(),
+        MARKET_ID,
         Decimal256::from_str("2.2")?,
-        &coins(10_000, bond_terms_1::BOND_TOKEN),
+        None,
+        None,
+        &coins(bond_terms_1::DEPOSIT, bond_terms_1::BOND_TOKEN),
     )?;
-    let bond_price_after = bond_contract.bond_price()?;
+    let bond_price_after = bond_contract.bond_price(MARKET_ID)?;
     println!("{} - {}", bond_price_before, bond_price_after);
     assert!(bond_price_before < bond_price_after);
 
     // Check that the bond goes down after some time
     chain.wait_blocks(VESTING_TERM / 10)?;
-    let bond_price_final = bond_contract.bond_price()?;
+    let bond_price_final = bond_contract.bond_price(MARKET_ID)?;
     assert!(bond_price_final < bond_price_after);
 
     // Rebase and check that exchange rate goes up
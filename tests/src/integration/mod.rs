@@ -13,8 +13,12 @@ pub mod test_constants {
 
     pub mod bond_terms_1 {
 
+        pub const MARKET_ID: u64 = 0;
         pub const BOND_TOKEN: &str = "inj";
-        pub const CONTROL_VARIABLE: &str = "70000000";
+        pub const BOND_TOKEN_DECIMALS: u8 = 18;
+        /// 1_000 INJ
+        pub const DEPOSIT: u128 = 1_000_000_000_000_000_000_000;
+        pub const CONTROL_VARIABLE: &str = "700";
         pub const MAX_DEBT: u128 = 100_000_000_000_000;
         pub const MAX_PAYOUT: &str = "100000000";
         pub const MINIMUM_PRICE: &str = "2";
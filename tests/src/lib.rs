@@ -8,6 +8,7 @@ pub mod deploy;
 pub mod exchange;
 #[cfg(test)]
 pub mod integration;
+pub mod sudo;
 pub mod tokenfactory;
 pub const AMOUNT_TO_CREATE_DENOM_TEST: u128 = 10_000_000_000_000_000_000u128;
 pub const FUNDS_MULTIPLIER: u128 = 100_000;
@@ -0,0 +1,14 @@
+use cosmwasm_schema::serde::Serialize;
+use cw_orch::injective_test_tube::InjectiveTestTube;
+use cw_orch::prelude::*;
+
+/// Executes `msg` on the `sudo` entry point of `contract`, like a governance proposal would
+pub fn wasm_sudo<M: Serialize>(
+    chain: &InjectiveTestTube,
+    contract: &Addr,
+    msg: &M,
+) -> anyhow::Result<()> {
+    chain.app.borrow().wasm_sudo(contract.as_str(), msg)?;
+
+    Ok(())
+}